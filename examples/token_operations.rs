@@ -10,6 +10,7 @@ use litesvm::LiteSVM;
 use solana_kite::{
     create_wallet, create_token_mint, create_associated_token_account,
     mint_tokens_to_account, get_token_account_balance, assert_token_balance,
+    assert_token_ui_balance, to_ui_amount,
     SolanaKiteError,
 };
 use solana_signer::Signer;
@@ -29,7 +30,7 @@ fn main() -> Result<(), SolanaKiteError> {
     println!("✅ Created user wallet: {}", user.pubkey());
 
     // Create a token mint with 6 decimals (like USDC)
-    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None)?;
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
     println!("✅ Created token mint: {}", mint);
     println!("   Decimals: 6");
     println!("   Mint authority: {}", mint_authority.pubkey());
@@ -40,11 +41,12 @@ fn main() -> Result<(), SolanaKiteError> {
         &user.pubkey(),
         &mint,
         &user,
+        &spl_token::ID,
     )?;
     println!("✅ Created associated token account: {}", user_token_account);
 
     // Check initial balance (should be 0)
-    let initial_balance = get_token_account_balance(&litesvm, &user_token_account)?;
+    let initial_balance = get_token_account_balance(&litesvm, &user_token_account, &spl_token::ID)?;
     println!("✅ Initial token balance: {} (raw units)", initial_balance);
     assert_eq!(initial_balance, 0);
 
@@ -56,13 +58,14 @@ fn main() -> Result<(), SolanaKiteError> {
         &user_token_account,
         mint_amount,
         &mint_authority,
+        &spl_token::ID,
     )?;
     println!("✅ Minted {} base units to user account", mint_amount);
 
     // Check the balance after minting
-    let final_balance = get_token_account_balance(&litesvm, &user_token_account)?;
+    let final_balance = get_token_account_balance(&litesvm, &user_token_account, &spl_token::ID)?;
     println!("✅ Final token balance: {} base units", final_balance);
-    println!("   That's {} tokens (with 6 decimals)", final_balance as f64 / 1_000_000.0);
+    println!("   That's {} tokens (with 6 decimals)", to_ui_amount(&litesvm, &mint, final_balance)?);
 
     // Assert the balance is correct
     assert_token_balance(&litesvm, &user_token_account, mint_amount, "Balance should match minted amount");
@@ -76,15 +79,17 @@ fn main() -> Result<(), SolanaKiteError> {
         &user_token_account,
         additional_mint,
         &mint_authority,
+        &spl_token::ID,
     )?;
     println!("✅ Minted additional {} base units", additional_mint);
 
-    let total_balance = get_token_account_balance(&litesvm, &user_token_account)?;
+    let total_balance = get_token_account_balance(&litesvm, &user_token_account, &spl_token::ID)?;
     let expected_total = mint_amount + additional_mint;
     println!("✅ Total balance after second mint: {} base units", total_balance);
-    println!("   That's {} tokens (with 6 decimals)", total_balance as f64 / 1_000_000.0);
+    println!("   That's {} tokens (with 6 decimals)", to_ui_amount(&litesvm, &mint, total_balance)?);
     
     assert_token_balance(&litesvm, &user_token_account, expected_total, "Total balance should be cumulative");
+    assert_token_ui_balance(&litesvm, &user_token_account, "1500");
     println!("✅ Cumulative balance assertion passed");
 
     println!("🎉 Token operations example completed successfully!");
@@ -92,7 +97,7 @@ fn main() -> Result<(), SolanaKiteError> {
     println!("   - Created 1 token mint with 6 decimals");
     println!("   - Created 1 associated token account");
     println!("   - Performed 2 mint operations");
-    println!("   - Final balance: {} tokens", total_balance as f64 / 1_000_000.0);
+    println!("   - Final balance: {} tokens", to_ui_amount(&litesvm, &mint, total_balance)?);
 
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,516 @@
+//! Decoding of Anchor program errors and events from transactions.
+
+use crate::error::{SolanaKiteError, TransactionFailure};
+use crate::logs::{parse_program_line, ProgramLine};
+use crate::transaction::TransactionResult;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use borsh::BorshDeserialize;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_program::hash::hashv;
+use solana_pubkey::Pubkey;
+
+/// Custom error codes at or above this value are defined by the program's own `#[error_code]` enum.
+///
+/// Codes below it are Anchor framework errors, such as constraint violations.
+pub const ANCHOR_ERROR_CODE_OFFSET: u32 = 6000;
+
+/// Prefix Anchor puts before the event in the instruction data of an `emit_cpi!` self-CPI.
+const EVENT_IX_TAG: u64 = 0x1d9a_cb51_2ea5_45e4;
+
+const ANCHOR_ERROR_PREFIX: &str = "Program log: AnchorError ";
+const ERROR_CODE_MARKER: &str = "Error Code: ";
+const ERROR_NUMBER_MARKER: &str = ". Error Number: ";
+const ERROR_MESSAGE_MARKER: &str = ". Error Message: ";
+
+/// Location in the program source where an Anchor error was raised.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorErrorSource {
+    /// Source file, relative to the program's workspace.
+    pub file: String,
+    /// Line number within the source file.
+    pub line: u32,
+}
+
+/// A decoded Anchor error, as logged by an Anchor program before it fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorErrorInfo {
+    /// Name of the error variant, e.g. `InsufficientFunds` or `ConstraintSeeds`.
+    pub error_name: String,
+    /// Error number, which is also the custom error code of the failed instruction.
+    pub error_number: u32,
+    /// The error's message.
+    pub error_message: String,
+    /// Where the error was raised, for errors thrown with `err!` or `require!`.
+    pub source: Option<AnchorErrorSource>,
+    /// The account that caused the error, for account constraint violations.
+    pub account_name: Option<String>,
+    /// The left and right values of a failed `require_eq!`-style comparison.
+    pub compared_values: Option<(String, String)>,
+    /// The underlying transaction failure.
+    pub failure: TransactionFailure,
+}
+
+impl AnchorErrorInfo {
+    /// Returns true if the error comes from the program's own error enum rather than Anchor itself.
+    pub fn is_program_error(&self) -> bool {
+        self.error_number >= ANCHOR_ERROR_CODE_OFFSET
+    }
+}
+
+/// Decodes the Anchor error behind a failed transaction.
+///
+/// Anchor programs log a line like `AnchorError occurred. Error Code: InsufficientFunds.
+/// Error Number: 6001. Error Message: Insufficient funds.` before returning the error
+/// number as a custom error code. This finds that log line for the failure's custom
+/// error code and decodes it.
+///
+/// # Arguments
+///
+/// * `failure` - The failed transaction to decode
+///
+/// # Returns
+///
+/// Returns the decoded error, or `None` if the transaction did not fail with a custom
+/// error code that an Anchor program logged.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{parse_anchor_error, TransactionFailure};
+/// use solana_instruction::error::InstructionError;
+/// use solana_transaction_error::TransactionError;
+///
+/// let failure = TransactionFailure {
+///     error: TransactionError::InstructionError(0, InstructionError::Custom(6001)),
+///     instruction_index: Some(0),
+///     custom_error_code: Some(6001),
+///     logs: vec![
+///         "Program log: AnchorError occurred. Error Code: InsufficientFunds. Error Number: 6001. Error Message: Insufficient funds.".to_string(),
+///     ],
+/// };
+///
+/// let anchor_error = parse_anchor_error(&failure).unwrap();
+/// assert_eq!(anchor_error.error_name, "InsufficientFunds");
+/// assert_eq!(anchor_error.error_message, "Insufficient funds");
+/// ```
+pub fn parse_anchor_error(failure: &TransactionFailure) -> Option<AnchorErrorInfo> {
+    let code = failure.custom_error_code?;
+
+    // Errors propagate out of CPIs, so the most recent matching log is the one that failed the transaction
+    failure
+        .logs
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(index, log)| {
+            let mut info = parse_anchor_error_log(log, failure)?;
+            if info.error_number != code {
+                return None;
+            }
+            info.compared_values = parse_compared_values(&failure.logs[index + 1..]);
+            Some(info)
+        })
+}
+
+/// Parses a single `AnchorError ...` log line.
+fn parse_anchor_error_log(log: &str, failure: &TransactionFailure) -> Option<AnchorErrorInfo> {
+    let rest = log.strip_prefix(ANCHOR_ERROR_PREFIX)?;
+
+    let mut source = None;
+    let mut account_name = None;
+    let details = if let Some(rest) = rest.strip_prefix("occurred. ") {
+        rest
+    } else if let Some(rest) = rest.strip_prefix("thrown in ") {
+        let (location, details) = rest.split_once(". ")?;
+        let (file, line) = location.rsplit_once(':')?;
+        source = Some(AnchorErrorSource {
+            file: file.to_string(),
+            line: line.parse().ok()?,
+        });
+        details
+    } else if let Some(rest) = rest.strip_prefix("caused by account: ") {
+        let (account, details) = rest.split_once(". ")?;
+        account_name = Some(account.to_string());
+        details
+    } else {
+        return None;
+    };
+
+    let details = details.strip_prefix(ERROR_CODE_MARKER)?;
+    let (error_name, details) = details.split_once(ERROR_NUMBER_MARKER)?;
+    let (error_number, error_message) = details.split_once(ERROR_MESSAGE_MARKER)?;
+
+    Some(AnchorErrorInfo {
+        error_name: error_name.to_string(),
+        error_number: error_number.parse().ok()?,
+        error_message: error_message
+            .strip_suffix('.')
+            .unwrap_or(error_message)
+            .to_string(),
+        source,
+        account_name,
+        compared_values: None,
+        failure: failure.clone(),
+    })
+}
+
+/// Parses the `Left:` / `Right:` logs Anchor writes after a failed comparison.
+fn parse_compared_values(logs: &[String]) -> Option<(String, String)> {
+    let values: Vec<&str> = logs
+        .iter()
+        .take(4)
+        .map(|log| log.strip_prefix("Program log: "))
+        .collect::<Option<_>>()?;
+    match values.as_slice() {
+        ["Left:", left, "Right:", right] => Some((left.to_string(), right.to_string())),
+        _ => None,
+    }
+}
+
+/// Computes the 8-byte discriminator Anchor prefixes to an event's data.
+///
+/// This is the first 8 bytes of the SHA-256 hash of `event:<EventName>`.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::anchor_event_discriminator;
+///
+/// let discriminator = anchor_event_discriminator("DepositEvent");
+/// assert_eq!(discriminator.len(), 8);
+/// ```
+pub fn anchor_event_discriminator(event_name: &str) -> [u8; 8] {
+    let hash = hashv(&[format!("event:{}", event_name).as_bytes()]);
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+/// Decodes the Anchor events of one type emitted by a program during a transaction.
+///
+/// Events emitted with `emit!` are found in `Program data:` logs, and events emitted
+/// with `emit_cpi!` are found in the program's self-CPI instructions. Either way, the
+/// event is identified by its discriminator and the rest of the data is deserialized
+/// with Borsh into `T`, which should have the same fields as the program's event struct.
+/// Events logged or self-invoked by any other program are ignored, even if they share
+/// the event's name.
+///
+/// # Arguments
+///
+/// * `result` - The result of the executed transaction
+/// * `program_id` - The program that emitted the events
+/// * `event_name` - Name of the event struct in the program, e.g. `DepositEvent`
+///
+/// # Returns
+///
+/// Returns the matching events in the order they were emitted, whichever way each
+/// one was emitted. If the logs were truncated, `emit_cpi!` events after the point
+/// of truncation follow the others, in instruction order.
+///
+/// # Errors
+///
+/// This function will return an error if a `Program data:` log from `program_id` isn't
+/// valid base64, or if an event with a matching discriminator can't be deserialized into `T`.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{anchor_event_discriminator, decode_anchor_events, TransactionResult};
+/// use base64::Engine;
+/// use borsh::{BorshDeserialize, BorshSerialize};
+/// use solana_pubkey::Pubkey;
+///
+/// #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+/// struct DepositEvent {
+///     amount: u64,
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let program_id = Pubkey::new_unique();
+/// # let mut event_data = anchor_event_discriminator("DepositEvent").to_vec();
+/// # event_data.extend(borsh::to_vec(&DepositEvent { amount: 500 })?);
+/// # let result = TransactionResult {
+/// #     signature: Default::default(),
+/// #     logs: vec![
+/// #         format!("Program {} invoke [1]", program_id),
+/// #         format!("Program data: {}", base64::engine::general_purpose::STANDARD.encode(&event_data)),
+/// #         format!("Program {} success", program_id),
+/// #     ],
+/// #     compute_units_consumed: 0,
+/// #     fee: 0,
+/// #     return_data: Default::default(),
+/// #     inner_instructions: vec![],
+/// #     account_keys: vec![],
+/// # };
+/// // `result` comes from sending a transaction to your Anchor program
+/// let events: Vec<DepositEvent> = decode_anchor_events(&result, &program_id, "DepositEvent")?;
+/// assert_eq!(events, vec![DepositEvent { amount: 500 }]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode_anchor_events<T: BorshDeserialize>(
+    result: &TransactionResult,
+    program_id: &Pubkey,
+    event_name: &str,
+) -> Result<Vec<T>, SolanaKiteError> {
+    let discriminator = anchor_event_discriminator(event_name);
+
+    // Every CPI logs an invoke line, so walking the logs visits the inner instructions
+    // in order, and places each emit_cpi! event among the emit! events
+    let mut inner_instructions = result.inner_instructions.iter().flatten();
+    let mut program_stack: Vec<Pubkey> = Vec::new();
+    let mut events_data = Vec::new();
+    for log in &result.logs {
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if program_stack.last() == Some(program_id) {
+                events_data.push(decode_log_data(data)?);
+            }
+            continue;
+        }
+        match parse_program_line(log) {
+            Some(ProgramLine::Invoke(invoked_program, depth)) => {
+                program_stack.truncate(depth.saturating_sub(1));
+                program_stack.push(invoked_program);
+                if depth > 1 {
+                    events_data.extend(
+                        inner_instructions
+                            .next()
+                            .and_then(|inner| emit_cpi_event_data(result, &inner.instruction, program_id)),
+                    );
+                }
+            }
+            Some(ProgramLine::Finished(_)) => {
+                program_stack.pop();
+            }
+            _ => {}
+        }
+    }
+    // Truncated logs stop before the last CPIs
+    events_data.extend(
+        inner_instructions.filter_map(|inner| emit_cpi_event_data(result, &inner.instruction, program_id)),
+    );
+
+    events_data
+        .iter()
+        .filter_map(|data| data.strip_prefix(discriminator.as_slice()))
+        .map(|event| {
+            T::try_from_slice(event).map_err(|e| {
+                SolanaKiteError::DeserializationFailed(format!("Failed to deserialize {} event: {}", event_name, e))
+            })
+        })
+        .collect()
+}
+
+/// Decodes a `Program data:` payload.
+fn decode_log_data(data: &str) -> Result<Vec<u8>, SolanaKiteError> {
+    // sol_log_data logs each field separately, Anchor's emit! logs a single one
+    data.split(' ')
+        .map(|chunk| BASE64.decode(chunk))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|chunks| chunks.concat())
+        .map_err(|e| SolanaKiteError::DeserializationFailed(format!("Failed to decode program data '{}': {}", data, e)))
+}
+
+/// Returns the event in an `emit_cpi!` self-CPI of `program_id`, if the instruction is one.
+fn emit_cpi_event_data(
+    result: &TransactionResult,
+    instruction: &CompiledInstruction,
+    program_id: &Pubkey,
+) -> Option<Vec<u8>> {
+    if result.account_keys.get(usize::from(instruction.program_id_index)) != Some(program_id) {
+        return None;
+    }
+    instruction
+        .data
+        .strip_prefix(EVENT_IX_TAG.to_le_bytes().as_slice())
+        .map(|data| data.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshSerialize;
+    use solana_instruction::error::InstructionError;
+    use solana_message::inner_instruction::InnerInstruction;
+    use solana_transaction_error::TransactionError;
+
+    #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+    struct DepositEvent {
+        amount: u64,
+        memo: String,
+    }
+
+    fn event_data(event_name: &str, event: &DepositEvent) -> Vec<u8> {
+        let mut data = anchor_event_discriminator(event_name).to_vec();
+        data.extend(borsh::to_vec(event).unwrap());
+        data
+    }
+
+    fn failure_with_logs(code: u32, logs: &[&str]) -> TransactionFailure {
+        TransactionFailure {
+            error: TransactionError::InstructionError(0, InstructionError::Custom(code)),
+            instruction_index: Some(0),
+            custom_error_code: Some(code),
+            logs: logs.iter().map(|log| log.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_anchor_error_variants() {
+        let failure = failure_with_logs(6001, &[
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS invoke [1]",
+            "Program log: Instruction: Withdraw",
+            "Program log: AnchorError thrown in programs/vault/src/lib.rs:42. Error Code: InsufficientFunds. Error Number: 6001. Error Message: Not enough funds. Try a smaller amount.",
+            "Program Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS failed: custom program error: 0x1771",
+        ]);
+        let info = parse_anchor_error(&failure).unwrap();
+        assert_eq!(info.error_name, "InsufficientFunds");
+        assert_eq!(info.error_number, 6001);
+        assert_eq!(info.error_message, "Not enough funds. Try a smaller amount");
+        assert_eq!(
+            info.source,
+            Some(AnchorErrorSource { file: "programs/vault/src/lib.rs".to_string(), line: 42 })
+        );
+        assert_eq!(info.account_name, None);
+        assert!(info.is_program_error());
+
+        let failure = failure_with_logs(2006, &[
+            "Program log: AnchorError caused by account: vault. Error Code: ConstraintSeeds. Error Number: 2006. Error Message: A seeds constraint was violated.",
+            "Program log: Left:",
+            "Program log: 11111111111111111111111111111111",
+            "Program log: Right:",
+            "Program log: SysvarRent111111111111111111111111111111111",
+        ]);
+        let info = parse_anchor_error(&failure).unwrap();
+        assert_eq!(info.error_name, "ConstraintSeeds");
+        assert_eq!(info.account_name, Some("vault".to_string()));
+        assert_eq!(
+            info.compared_values,
+            Some((
+                "11111111111111111111111111111111".to_string(),
+                "SysvarRent111111111111111111111111111111111".to_string()
+            ))
+        );
+        assert!(!info.is_program_error());
+
+        let failure = failure_with_logs(6000, &[
+            "Program log: AnchorError occurred. Error Code: Unauthorized. Error Number: 6000. Error Message: Unauthorized.",
+        ]);
+        let info = parse_anchor_error(&failure).unwrap();
+        assert_eq!(info.error_name, "Unauthorized");
+        assert_eq!(info.source, None);
+        assert_eq!(info.compared_values, None);
+    }
+
+    #[test]
+    fn test_parse_anchor_error_requires_matching_code() {
+        let failure = failure_with_logs(1, &[
+            "Program log: AnchorError occurred. Error Code: Unauthorized. Error Number: 6000. Error Message: Unauthorized.",
+        ]);
+        assert!(parse_anchor_error(&failure).is_none());
+
+        let failure = failure_with_logs(1, &["Program log: Error: insufficient funds"]);
+        assert!(parse_anchor_error(&failure).is_none());
+    }
+
+    #[test]
+    fn test_decode_anchor_events() {
+        let program = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let first = DepositEvent { amount: 1, memo: "emit".to_string() };
+        let second = DepositEvent { amount: 2, memo: "emit_cpi".to_string() };
+        let third = DepositEvent { amount: 3, memo: "emit after emit_cpi".to_string() };
+        let impostor = DepositEvent { amount: 4, memo: "other program".to_string() };
+
+        let cpi_data = |event: &DepositEvent| {
+            let mut data = EVENT_IX_TAG.to_le_bytes().to_vec();
+            data.extend(event_data("DepositEvent", event));
+            data
+        };
+        let log_data = |event_name: &str, event: &DepositEvent| {
+            format!("Program data: {}", BASE64.encode(event_data(event_name, event)))
+        };
+
+        let result = TransactionResult {
+            signature: Default::default(),
+            logs: vec![
+                format!("Program {} invoke [1]", program),
+                "Program log: Instruction: Deposit".to_string(),
+                log_data("WithdrawEvent", &first),
+                log_data("DepositEvent", &first),
+                format!("Program {} invoke [2]", program),
+                format!("Program {} success", program),
+                log_data("DepositEvent", &third),
+                format!("Program {} success", program),
+                format!("Program {} invoke [1]", other_program),
+                log_data("DepositEvent", &impostor),
+                "Program data: not-base64!".to_string(),
+                format!("Program {} invoke [2]", other_program),
+                format!("Program {} success", other_program),
+                format!("Program {} success", other_program),
+            ],
+            compute_units_consumed: 0,
+            fee: 0,
+            return_data: Default::default(),
+            inner_instructions: vec![
+                vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(1, cpi_data(&second), vec![2]),
+                    stack_height: 2,
+                }],
+                vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(3, cpi_data(&impostor), vec![4]),
+                    stack_height: 2,
+                }],
+            ],
+            account_keys: vec![Pubkey::new_unique(), program, Pubkey::new_unique(), other_program, Pubkey::new_unique()],
+        };
+
+        // Events come back in execution order, and only from the given program
+        let events: Vec<DepositEvent> = decode_anchor_events(&result, &program, "DepositEvent").unwrap();
+        assert_eq!(events, vec![first, second, third]);
+
+        // A matching discriminator with the wrong layout is an error
+        let error = decode_anchor_events::<u8>(&result, &program, "DepositEvent").unwrap_err();
+        assert!(matches!(error, SolanaKiteError::DeserializationFailed(_)));
+
+        // So is program data from the given program that isn't base64, while the other program's is ignored
+        let mut result = result;
+        result.logs.insert(3, "Program data: not-base64!".to_string());
+        let error = decode_anchor_events::<DepositEvent>(&result, &program, "DepositEvent").unwrap_err();
+        assert!(matches!(error, SolanaKiteError::DeserializationFailed(_)));
+    }
+
+    #[test]
+    fn test_decode_anchor_events_from_truncated_logs() {
+        let program = Pubkey::new_unique();
+        let first = DepositEvent { amount: 1, memo: "emit_cpi".to_string() };
+        let second = DepositEvent { amount: 2, memo: "emit_cpi after truncation".to_string() };
+        let inner_instruction = |event: &DepositEvent| {
+            let mut data = EVENT_IX_TAG.to_le_bytes().to_vec();
+            data.extend(event_data("DepositEvent", event));
+            InnerInstruction {
+                instruction: CompiledInstruction::new_from_raw_parts(1, data, vec![]),
+                stack_height: 2,
+            }
+        };
+
+        let result = TransactionResult {
+            signature: Default::default(),
+            logs: vec![
+                format!("Program {} invoke [1]", program),
+                format!("Program {} invoke [2]", program),
+                format!("Program {} success", program),
+                "Log truncated".to_string(),
+            ],
+            compute_units_consumed: 0,
+            fee: 0,
+            return_data: Default::default(),
+            inner_instructions: vec![vec![inner_instruction(&first), inner_instruction(&second)]],
+            account_keys: vec![Pubkey::new_unique(), program],
+        };
+
+        let events: Vec<DepositEvent> = decode_anchor_events(&result, &program, "DepositEvent").unwrap();
+        assert_eq!(events, vec![first, second]);
+    }
+}
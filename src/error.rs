@@ -1,5 +1,11 @@
 //! Error types for Solana Kite operations.
 
+use crate::anchor::{parse_anchor_error, AnchorErrorInfo};
+use crate::logs::{format_call_trace, parse_program_logs, ProgramInvocation};
+use litesvm::types::FailedTransactionMetadata;
+use solana_instruction::error::InstructionError;
+use solana_pubkey::Pubkey;
+use solana_transaction_error::TransactionError;
 use std::fmt;
 
 /// Main error type for Solana Kite operations.
@@ -7,12 +13,22 @@ use std::fmt;
 pub enum SolanaKiteError {
     /// Transaction failed with an error message.
     TransactionFailed(String),
+    /// Transaction was rejected or failed while executing, with the details reported by LiteSVM.
+    TransactionExecutionFailed(TransactionFailure),
+    /// Transaction failed with an error logged by an Anchor program.
+    AnchorError(Box<AnchorErrorInfo>),
+    /// Transaction was not sent because these required signers were not provided.
+    MissingSigners(Vec<Pubkey>),
+    /// Transaction message could not be compiled or signed.
+    TransactionBuildFailed(String),
     /// Program deployment failed.
     ProgramDeploymentFailed(String),
     /// Token operation failed.
     TokenOperationFailed(String),
     /// Account operation failed.
     AccountOperationFailed(String),
+    /// Program output, such as an event or return data, could not be deserialized.
+    DeserializationFailed(String),
     /// I/O error occurred.
     IoError(std::io::Error),
 }
@@ -23,6 +39,23 @@ impl fmt::Display for SolanaKiteError {
             SolanaKiteError::TransactionFailed(msg) => {
                 write!(f, "Transaction failed: {}", msg)
             }
+            SolanaKiteError::TransactionExecutionFailed(failure) => {
+                write!(f, "Transaction failed: {}", failure.error)
+            }
+            SolanaKiteError::AnchorError(info) => {
+                write!(
+                    f,
+                    "Transaction failed: Anchor error {} ({}): {}",
+                    info.error_name, info.error_number, info.error_message
+                )
+            }
+            SolanaKiteError::MissingSigners(pubkeys) => {
+                let pubkeys: Vec<String> = pubkeys.iter().map(|pubkey| pubkey.to_string()).collect();
+                write!(f, "Transaction is missing required signers: {}", pubkeys.join(", "))
+            }
+            SolanaKiteError::TransactionBuildFailed(msg) => {
+                write!(f, "Transaction build failed: {}", msg)
+            }
             SolanaKiteError::ProgramDeploymentFailed(msg) => {
                 write!(f, "Program deployment failed: {}", msg)
             }
@@ -32,6 +65,9 @@ impl fmt::Display for SolanaKiteError {
             SolanaKiteError::AccountOperationFailed(msg) => {
                 write!(f, "Account operation failed: {}", msg)
             }
+            SolanaKiteError::DeserializationFailed(msg) => {
+                write!(f, "Deserialization failed: {}", msg)
+            }
             SolanaKiteError::IoError(err) => {
                 write!(f, "I/O error: {}", err)
             }
@@ -39,6 +75,78 @@ impl fmt::Display for SolanaKiteError {
     }
 }
 
+impl SolanaKiteError {
+    /// Returns the details of the failed transaction, if this error came from one.
+    pub fn transaction_failure(&self) -> Option<&TransactionFailure> {
+        match self {
+            SolanaKiteError::TransactionExecutionFailed(failure) => Some(failure),
+            SolanaKiteError::AnchorError(info) => Some(&info.failure),
+            _ => None,
+        }
+    }
+}
+
+impl From<FailedTransactionMetadata> for SolanaKiteError {
+    fn from(failed: FailedTransactionMetadata) -> Self {
+        let failure = TransactionFailure::from(failed);
+        match parse_anchor_error(&failure) {
+            Some(info) => SolanaKiteError::AnchorError(Box::new(info)),
+            None => SolanaKiteError::TransactionExecutionFailed(failure),
+        }
+    }
+}
+
+/// Details of a transaction that LiteSVM rejected or that failed during execution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionFailure {
+    /// The error reported for the transaction.
+    pub error: TransactionError,
+    /// Index of the instruction that failed, if the failure came from an instruction.
+    pub instruction_index: Option<u8>,
+    /// The program's custom error code, if the failing instruction returned one.
+    pub custom_error_code: Option<u32>,
+    /// Program logs emitted before the transaction failed.
+    pub logs: Vec<String>,
+}
+
+impl TransactionFailure {
+    /// Returns the instruction error, if the failure came from an instruction.
+    pub fn instruction_error(&self) -> Option<&InstructionError> {
+        match &self.error {
+            TransactionError::InstructionError(_, instruction_error) => Some(instruction_error),
+            _ => None,
+        }
+    }
+
+    /// Parses the logs into one [`ProgramInvocation`] per executed top-level instruction.
+    pub fn invocations(&self) -> Vec<ProgramInvocation> {
+        parse_program_logs(&self.logs)
+    }
+
+    /// Returns the logs as an indented call trace, showing where the transaction failed.
+    pub fn call_trace(&self) -> String {
+        format_call_trace(&self.logs)
+    }
+}
+
+impl From<FailedTransactionMetadata> for TransactionFailure {
+    fn from(failed: FailedTransactionMetadata) -> Self {
+        let (instruction_index, custom_error_code) = match &failed.err {
+            TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+                (Some(*index), Some(*code))
+            }
+            TransactionError::InstructionError(index, _) => (Some(*index), None),
+            _ => (None, None),
+        };
+        TransactionFailure {
+            error: failed.err,
+            instruction_index,
+            custom_error_code,
+            logs: failed.meta.logs,
+        }
+    }
+}
+
 impl std::error::Error for SolanaKiteError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
@@ -6,11 +6,13 @@
 //!
 //! ## Features
 //!
-//! - **Program Deployment**: Deploy programs to a test environment
-//! - **Transaction Utilities**: Send transactions from instructions with proper signing
-//! - **Token Operations**: Create mints, associated token accounts, and mint tokens
+//! - **Program Deployment**: Deploy programs to a test environment, including upgradeable programs you can upgrade
+//! - **Transaction Utilities**: Send legacy or versioned transactions from instructions with proper signing
+//! - **Token Operations**: Create SPL Token and Token-2022 mints (with extensions), associated token accounts, and mint, transfer and burn tokens
+//! - **NFTs**: Create Metaplex NFTs with metadata, master editions and verified collections
 //! - **Account Management**: Create wallets, check balances, and manage account state
 //! - **PDA Utilities**: Generate Program Derived Addresses with type-safe seed handling
+//! - **Anchor Support**: Deploy Anchor workspaces, and decode Anchor errors and events from transactions
 //!
 //! ## Example
 //!
@@ -20,23 +22,55 @@
 //!
 //! let mut litesvm = LiteSVM::new();
 //! let wallet = create_wallet(&mut litesvm, 1_000_000_000).unwrap(); // 1 SOL
-//! let mint = create_token_mint(&mut litesvm, &wallet, 6, None).unwrap(); // 6 decimals
+//! let mint = create_token_mint(&mut litesvm, &wallet, 6, None, None, &spl_token::ID).unwrap(); // 6 decimals
 //! ```
 
+pub mod anchor;
 pub mod error;
+pub mod logs;
+pub mod lookup_table;
+pub mod metaplex;
+pub mod mint_builder;
 pub mod program;
 pub mod token;
 pub mod transaction;
+pub mod transaction_builder;
 pub mod wallet;
 pub mod pda;
 
-pub use error::SolanaKiteError;
-pub use program::deploy_program;
+pub use anchor::{
+    anchor_event_discriminator, decode_anchor_events, parse_anchor_error, AnchorErrorInfo,
+    AnchorErrorSource,
+};
+pub use error::{SolanaKiteError, TransactionFailure};
+pub use logs::{format_call_trace, parse_program_logs, InvocationOutcome, ProgramInvocation};
+pub use lookup_table::{
+    create_lookup_table, deactivate_lookup_table, extend_lookup_table, freeze_lookup_table,
+    get_lookup_table_addresses,
+};
+pub use metaplex::{create_collection_nft, create_nft, deploy_token_metadata_program, Nft};
+pub use mint_builder::MintBuilder;
+pub use program::{
+    deploy_anchor_workspace, deploy_program, deploy_program_from_bytes, deploy_upgradeable_program,
+    get_upgrade_authority, set_upgrade_authority, upgrade_program,
+};
 pub use token::{
-    create_associated_token_account, create_token_mint, get_token_account_balance,
-    assert_token_balance, mint_tokens_to_account,
+    create_associated_token_account, create_associated_token_account_idempotent, create_multisig,
+    create_token_account, create_token_mint, get_mint, get_token_account,
+    get_token_account_balance, assert_token_balance, assert_token_ui_balance, approve_delegate,
+    approve_delegate_checked, burn_tokens, close_token_account, freeze_token_account,
+    from_ui_amount, mint_tokens_to_account, revoke_delegate, set_mint_state, set_token_authority,
+    set_token_balance, thaw_token_account, to_ui_amount, transfer_tokens, transfer_tokens_checked,
+    unwrap_sol, wrap_sol, TokenAccount, TokenAuthority, TokenMint,
+};
+pub use transaction::{
+    assert_instruction_compute_units, assert_instruction_error,
+    assert_transaction_fails_with_custom_error, call_view_instruction,
+    send_transaction_from_instructions, send_transaction_with_compute_budget,
+    send_versioned_transaction_from_instructions, simulate_transaction_from_instructions,
+    ComputeBudgetOptions, SimulationResult, TransactionResult,
 };
-pub use transaction::send_transaction_from_instructions;
+pub use transaction_builder::TransactionBuilder;
 pub use wallet::{create_wallet, create_wallets};
 pub use pda::{get_pda_and_bump, Seed};
 
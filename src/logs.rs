@@ -0,0 +1,295 @@
+//! Parsing of program logs into a tree of program invocations.
+
+use solana_pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+
+/// How a program invocation ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationOutcome {
+    /// The program returned successfully.
+    Success,
+    /// The program failed with this error message.
+    Failed(String),
+    /// The logs end before the program finished, for example because they were truncated.
+    Incomplete,
+}
+
+/// A single program invocation, along with the invocations it made through CPI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramInvocation {
+    /// The invoked program.
+    pub program_id: Pubkey,
+    /// Invocation depth, starting at 1 for top-level instructions.
+    pub depth: usize,
+    /// Messages the program logged with `msg!`, without the `Program log: ` prefix.
+    pub logs: Vec<String>,
+    /// Base64 payloads the program logged with `sol_log_data`, such as Anchor events.
+    pub data: Vec<String>,
+    /// Compute units consumed, if the program reported them. Builtin programs don't.
+    pub compute_units_consumed: Option<u64>,
+    /// How the invocation ended.
+    pub outcome: InvocationOutcome,
+    /// Invocations this program made through CPI, in order.
+    pub inner_invocations: Vec<ProgramInvocation>,
+}
+
+impl ProgramInvocation {
+    fn new(program_id: Pubkey, depth: usize) -> Self {
+        Self {
+            program_id,
+            depth,
+            logs: Vec::new(),
+            data: Vec::new(),
+            compute_units_consumed: None,
+            outcome: InvocationOutcome::Incomplete,
+            inner_invocations: Vec::new(),
+        }
+    }
+
+    /// Returns true if the program returned successfully.
+    pub fn succeeded(&self) -> bool {
+        self.outcome == InvocationOutcome::Success
+    }
+
+    /// Returns every invocation nested under this one, depth first.
+    pub fn descendants(&self) -> Vec<&ProgramInvocation> {
+        let mut descendants = Vec::new();
+        for inner in &self.inner_invocations {
+            descendants.push(inner);
+            descendants.extend(inner.descendants());
+        }
+        descendants
+    }
+
+    /// Returns the nested invocations of `program_id`, at any depth.
+    pub fn inner_invocations_of(&self, program_id: &Pubkey) -> Vec<&ProgramInvocation> {
+        self.descendants()
+            .into_iter()
+            .filter(|invocation| &invocation.program_id == program_id)
+            .collect()
+    }
+
+    fn write_trace(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let padding = "  ".repeat(indent);
+        write!(f, "{}[{}] {}", padding, self.depth, self.program_id)?;
+        if let Some(consumed) = self.compute_units_consumed {
+            write!(f, " ({} CU)", consumed)?;
+        }
+        match &self.outcome {
+            InvocationOutcome::Success => writeln!(f, ": success")?,
+            InvocationOutcome::Failed(error) => writeln!(f, ": failed: {}", error)?,
+            InvocationOutcome::Incomplete => writeln!(f, ": incomplete")?,
+        }
+        for log in &self.logs {
+            writeln!(f, "{}    {}", padding, log)?;
+        }
+        for data in &self.data {
+            writeln!(f, "{}    data: {}", padding, data)?;
+        }
+        for inner in &self.inner_invocations {
+            inner.write_trace(f, indent + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the invocation and its inner invocations as an indented call trace.
+impl fmt::Display for ProgramInvocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_trace(f, 0)
+    }
+}
+
+/// Parses transaction logs into a tree of program invocations.
+///
+/// Each top-level instruction becomes one [`ProgramInvocation`], with CPIs nested
+/// inside it. Lines the parser doesn't recognise are kept in the `logs` of the
+/// invocation they appear in.
+///
+/// # Arguments
+///
+/// * `logs` - The transaction's logs, as returned by LiteSVM
+///
+/// # Returns
+///
+/// Returns one invocation per top-level instruction, in order.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{parse_program_logs, InvocationOutcome};
+///
+/// let logs = vec![
+///     "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL invoke [1]".to_string(),
+///     "Program log: Create".to_string(),
+///     "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+///     "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 1595 of 191410 compute units".to_string(),
+///     "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success".to_string(),
+///     "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL consumed 10185 of 200000 compute units".to_string(),
+///     "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL success".to_string(),
+/// ];
+///
+/// let invocations = parse_program_logs(&logs);
+/// assert_eq!(invocations.len(), 1);
+/// assert_eq!(invocations[0].logs, vec!["Create"]);
+/// assert_eq!(invocations[0].inner_invocations[0].compute_units_consumed, Some(1595));
+/// assert_eq!(invocations[0].outcome, InvocationOutcome::Success);
+/// ```
+pub fn parse_program_logs(logs: &[String]) -> Vec<ProgramInvocation> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<ProgramInvocation> = Vec::new();
+
+    for log in logs {
+        if let Some(message) = log.strip_prefix("Program log: ") {
+            if let Some(current) = stack.last_mut() {
+                current.logs.push(message.to_string());
+            }
+            continue;
+        }
+        if let Some(data) = log.strip_prefix("Program data: ") {
+            if let Some(current) = stack.last_mut() {
+                current.data.push(data.to_string());
+            }
+            continue;
+        }
+
+        match parse_program_line(log) {
+            Some(ProgramLine::Invoke(program_id, depth)) => {
+                stack.push(ProgramInvocation::new(program_id, depth));
+            }
+            Some(ProgramLine::Consumed(consumed)) => {
+                if let Some(current) = stack.last_mut() {
+                    current.compute_units_consumed = Some(consumed);
+                }
+            }
+            Some(ProgramLine::Finished(outcome)) => {
+                if let Some(mut finished) = stack.pop() {
+                    finished.outcome = outcome;
+                    attach(&mut stack, &mut roots, finished);
+                }
+            }
+            None => {
+                if let Some(current) = stack.last_mut() {
+                    current.logs.push(log.to_string());
+                }
+            }
+        }
+    }
+
+    // Anything still open when the logs end never finished
+    while let Some(unfinished) = stack.pop() {
+        attach(&mut stack, &mut roots, unfinished);
+    }
+
+    roots
+}
+
+/// Formats transaction logs as an indented call trace, for readable test failures.
+pub fn format_call_trace(logs: &[String]) -> String {
+    parse_program_logs(logs)
+        .iter()
+        .map(|invocation| invocation.to_string())
+        .collect()
+}
+
+fn attach(stack: &mut [ProgramInvocation], roots: &mut Vec<ProgramInvocation>, invocation: ProgramInvocation) {
+    match stack.last_mut() {
+        Some(parent) => parent.inner_invocations.push(invocation),
+        None => roots.push(invocation),
+    }
+}
+
+pub(crate) enum ProgramLine {
+    Invoke(Pubkey, usize),
+    Consumed(u64),
+    Finished(InvocationOutcome),
+}
+
+/// Parses the runtime's `Program <id> ...` lines.
+pub(crate) fn parse_program_line(log: &str) -> Option<ProgramLine> {
+    let (program_id, rest) = log.strip_prefix("Program ")?.split_once(' ')?;
+    let program_id = Pubkey::from_str(program_id).ok()?;
+
+    if let Some(depth) = rest.strip_prefix("invoke [").and_then(|rest| rest.strip_suffix(']')) {
+        return Some(ProgramLine::Invoke(program_id, depth.parse().ok()?));
+    }
+    if let Some(consumed) = rest
+        .strip_prefix("consumed ")
+        .and_then(|rest| rest.strip_suffix(" compute units"))
+        .and_then(|rest| rest.split_once(" of "))
+    {
+        return Some(ProgramLine::Consumed(consumed.0.parse().ok()?));
+    }
+    if rest == "success" {
+        return Some(ProgramLine::Finished(InvocationOutcome::Success));
+    }
+    if let Some(error) = rest.strip_prefix("failed: ") {
+        return Some(ProgramLine::Finished(InvocationOutcome::Failed(error.to_string())));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_nested_failure() {
+        let program = Pubkey::new_unique();
+        let token_program = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let invocations = parse_program_logs(&logs(&[
+            &format!("Program {} invoke [1]", program),
+            "Program log: Instruction: Withdraw",
+            "Program data: AQID",
+            &format!("Program {} invoke [2]", token_program),
+            "Program log: Instruction: Transfer",
+            &format!("Program {} consumed 4645 of 190000 compute units", token_program),
+            &format!("Program {} success", token_program),
+            &format!("Program {} invoke [2]", token_program),
+            "Program log: Error: insufficient funds",
+            &format!("Program {} consumed 4381 of 180000 compute units", token_program),
+            &format!("Program {} failed: custom program error: 0x1", token_program),
+            &format!("Program {} consumed 20000 of 200000 compute units", program),
+            &format!("Program {} failed: custom program error: 0x1", program),
+        ]));
+
+        assert_eq!(invocations.len(), 1);
+        let top = &invocations[0];
+        assert_eq!(top.program_id, program);
+        assert_eq!(top.logs, vec!["Instruction: Withdraw"]);
+        assert_eq!(top.data, vec!["AQID"]);
+        assert_eq!(top.compute_units_consumed, Some(20000));
+        assert_eq!(top.outcome, InvocationOutcome::Failed("custom program error: 0x1".to_string()));
+        assert_eq!(top.inner_invocations_of(&token_program).len(), 2);
+        assert!(top.inner_invocations[0].succeeded());
+        assert_eq!(top.inner_invocations[1].depth, 2);
+        assert_eq!(top.inner_invocations[1].logs, vec!["Error: insufficient funds"]);
+
+        let trace = format_call_trace(&logs(&[
+            &format!("Program {} invoke [1]", program),
+            "Program log: Hello",
+            &format!("Program {} success", program),
+        ]));
+        assert_eq!(trace, format!("[1] {}: success\n    Hello\n", program));
+    }
+
+    #[test]
+    fn test_parse_truncated_logs() {
+        let program = Pubkey::new_unique();
+        let invocations = parse_program_logs(&logs(&[
+            &format!("Program {} invoke [1]", program),
+            &format!("Program {} invoke [2]", program),
+            "Log truncated",
+        ]));
+
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].outcome, InvocationOutcome::Incomplete);
+        assert_eq!(invocations[0].inner_invocations[0].logs, vec!["Log truncated"]);
+        assert_eq!(invocations[0].inner_invocations[0].outcome, InvocationOutcome::Incomplete);
+    }
+}
@@ -0,0 +1,293 @@
+//! Address lookup table utilities for versioned transactions.
+
+use crate::error::SolanaKiteError;
+use crate::transaction::send_transaction_from_instructions;
+use litesvm::LiteSVM;
+use solana_address_lookup_table_interface::instruction as lookup_table_instruction;
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_keypair::Keypair;
+use solana_message::AddressLookupTableAccount;
+use solana_program::clock::Clock;
+use solana_program::slot_hashes::SlotHashes;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+/// Most addresses added by a single extend instruction, keeping each transaction under the size limit.
+const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Creates a new address lookup table.
+///
+/// The table address is derived from the authority and the current slot. LiteSVM only
+/// records the hash of its first slot, so the current slot is added to the `SlotHashes`
+/// sysvar if it is missing, as it would be on a real cluster.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `authority` - Public key of the account that may extend, freeze and deactivate the table
+/// * `payer` - Keypair that will pay for the table account and sign the transaction
+///
+/// # Returns
+///
+/// Returns the address of the new lookup table.
+///
+/// # Errors
+///
+/// This function will return an error if the table creation fails, including when
+/// the authority already created a table in the current slot.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_lookup_table, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+///
+/// let lookup_table = create_lookup_table(&mut litesvm, &authority.pubkey(), &authority)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_lookup_table(
+    litesvm: &mut LiteSVM,
+    authority: &Pubkey,
+    payer: &Keypair,
+) -> Result<Pubkey, SolanaKiteError> {
+    let slot = litesvm.get_sysvar::<Clock>().slot;
+    let mut slot_hashes = litesvm.get_sysvar::<SlotHashes>();
+    if slot_hashes.get(&slot).is_none() {
+        slot_hashes.add(slot, litesvm.latest_blockhash());
+        litesvm.set_sysvar(&slot_hashes);
+    }
+
+    let (create_instruction, lookup_table) =
+        lookup_table_instruction::create_lookup_table(*authority, payer.pubkey(), slot);
+
+    send_transaction_from_instructions(litesvm, vec![create_instruction], &[payer], &payer.pubkey())?;
+
+    Ok(lookup_table)
+}
+
+/// Adds addresses to an address lookup table.
+///
+/// Addresses are added in batches, one transaction per batch. Addresses added to a
+/// table only become usable in a later slot, so after extending the table this warps
+/// LiteSVM to the next slot, letting the table be used straight away. The warp moves
+/// the `Clock` sysvar forward for the rest of the test too. If `new_addresses` is
+/// empty, nothing is sent and the clock isn't changed.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `lookup_table` - Address of the lookup table
+/// * `authority` - The table's authority, which also pays for the extra table space
+/// * `new_addresses` - Addresses to add to the table
+///
+/// # Errors
+///
+/// This function will return an error if extending the table fails, for example
+/// because the table is frozen or deactivated.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_lookup_table, extend_lookup_table, get_lookup_table_addresses, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let lookup_table = create_lookup_table(&mut litesvm, &authority.pubkey(), &authority)?;
+///
+/// let addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+/// extend_lookup_table(&mut litesvm, &lookup_table, &authority, addresses.clone())?;
+///
+/// assert_eq!(get_lookup_table_addresses(&litesvm, &lookup_table)?, addresses);
+/// # Ok(())
+/// # }
+/// ```
+pub fn extend_lookup_table(
+    litesvm: &mut LiteSVM,
+    lookup_table: &Pubkey,
+    authority: &Keypair,
+    new_addresses: Vec<Pubkey>,
+) -> Result<(), SolanaKiteError> {
+    if new_addresses.is_empty() {
+        return Ok(());
+    }
+
+    for batch in new_addresses.chunks(MAX_ADDRESSES_PER_EXTEND) {
+        let extend_instruction = lookup_table_instruction::extend_lookup_table(
+            *lookup_table,
+            authority.pubkey(),
+            Some(authority.pubkey()),
+            batch.to_vec(),
+        );
+        send_transaction_from_instructions(
+            litesvm,
+            vec![extend_instruction],
+            &[authority],
+            &authority.pubkey(),
+        )?;
+    }
+
+    let slot = litesvm.get_sysvar::<Clock>().slot;
+    litesvm.warp_to_slot(slot + 1);
+
+    Ok(())
+}
+
+/// Freezes an address lookup table so it can never be changed or closed.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `lookup_table` - Address of the lookup table
+/// * `authority` - The table's authority
+///
+/// # Errors
+///
+/// This function will return an error if freezing fails, for example because the
+/// table is empty.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_lookup_table, extend_lookup_table, freeze_lookup_table, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let lookup_table = create_lookup_table(&mut litesvm, &authority.pubkey(), &authority)?;
+/// extend_lookup_table(&mut litesvm, &lookup_table, &authority, vec![Pubkey::new_unique()])?;
+///
+/// freeze_lookup_table(&mut litesvm, &lookup_table, &authority)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn freeze_lookup_table(
+    litesvm: &mut LiteSVM,
+    lookup_table: &Pubkey,
+    authority: &Keypair,
+) -> Result<(), SolanaKiteError> {
+    let freeze_instruction =
+        lookup_table_instruction::freeze_lookup_table(*lookup_table, authority.pubkey());
+    send_transaction_from_instructions(litesvm, vec![freeze_instruction], &[authority], &authority.pubkey())?;
+    Ok(())
+}
+
+/// Deactivates an address lookup table, so it can no longer be extended.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `lookup_table` - Address of the lookup table
+/// * `authority` - The table's authority
+///
+/// # Errors
+///
+/// This function will return an error if deactivation fails, for example because the
+/// table is frozen.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_lookup_table, deactivate_lookup_table, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let lookup_table = create_lookup_table(&mut litesvm, &authority.pubkey(), &authority)?;
+///
+/// deactivate_lookup_table(&mut litesvm, &lookup_table, &authority)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn deactivate_lookup_table(
+    litesvm: &mut LiteSVM,
+    lookup_table: &Pubkey,
+    authority: &Keypair,
+) -> Result<(), SolanaKiteError> {
+    let deactivate_instruction =
+        lookup_table_instruction::deactivate_lookup_table(*lookup_table, authority.pubkey());
+    send_transaction_from_instructions(litesvm, vec![deactivate_instruction], &[authority], &authority.pubkey())?;
+    Ok(())
+}
+
+/// Gets the addresses stored in an address lookup table.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `lookup_table` - Address of the lookup table
+///
+/// # Returns
+///
+/// Returns the table's addresses, in the order they were added.
+///
+/// # Errors
+///
+/// This function will return an error if the account doesn't exist, isn't owned by the
+/// address lookup table program, or isn't a lookup table.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_lookup_table, get_lookup_table_addresses, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let lookup_table = create_lookup_table(&mut litesvm, &authority.pubkey(), &authority)?;
+///
+/// assert!(get_lookup_table_addresses(&litesvm, &lookup_table)?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub fn get_lookup_table_addresses(
+    litesvm: &LiteSVM,
+    lookup_table: &Pubkey,
+) -> Result<Vec<Pubkey>, SolanaKiteError> {
+    let account = litesvm
+        .get_account(lookup_table)
+        .ok_or_else(|| SolanaKiteError::AccountOperationFailed(format!("Lookup table {} not found", lookup_table)))?;
+    if account.owner != solana_address_lookup_table_interface::program::id() {
+        return Err(SolanaKiteError::AccountOperationFailed(format!(
+            "Lookup table {} is owned by {}, not the address lookup table program",
+            lookup_table, account.owner
+        )));
+    }
+
+    let table = AddressLookupTable::deserialize(&account.data)
+        .map_err(|e| SolanaKiteError::AccountOperationFailed(format!("Failed to parse lookup table {}: {:?}", lookup_table, e)))?;
+
+    Ok(table.addresses.to_vec())
+}
+
+/// Loads lookup tables from LiteSVM in the form needed to compile a v0 message.
+pub(crate) fn load_lookup_table_accounts(
+    litesvm: &LiteSVM,
+    lookup_tables: &[Pubkey],
+) -> Result<Vec<AddressLookupTableAccount>, SolanaKiteError> {
+    lookup_tables
+        .iter()
+        .map(|lookup_table| {
+            Ok(AddressLookupTableAccount {
+                key: *lookup_table,
+                addresses: get_lookup_table_addresses(litesvm, lookup_table)?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,249 @@
+//! Metaplex Token Metadata utilities for creating NFTs and collections.
+//!
+//! LiteSVM doesn't ship the Token Metadata program, so load it from a `.so` file
+//! first with [`deploy_token_metadata_program`]. You can dump the program from
+//! mainnet with `solana program dump metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so`.
+//!
+//! The program binary isn't in this repository, so the NFT helpers are not tested in
+//! CI. Their end-to-end test is ignored unless you dump the binary locally, see
+//! `tests/fixtures/README.md`.
+
+use crate::error::SolanaKiteError;
+use crate::program::deploy_program;
+use crate::token::{create_associated_token_account, create_token_mint, mint_tokens_to_account};
+use crate::transaction::send_transaction_from_instructions;
+use litesvm::LiteSVM;
+use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+use mpl_token_metadata::instructions::{
+    CreateMasterEditionV3Builder, CreateMetadataAccountV3Builder, VerifyCollectionV1Builder,
+};
+use mpl_token_metadata::types::{Collection, CollectionDetails, Creator, DataV2};
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use std::path::Path;
+
+/// The accounts that make up an NFT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Nft {
+    /// The NFT's mint, with 0 decimals and a supply of 1.
+    pub mint: Pubkey,
+    /// The authority's associated token account, which holds the NFT.
+    pub token_account: Pubkey,
+    /// The Token Metadata account, holding the name, symbol, URI and collection.
+    pub metadata: Pubkey,
+    /// The master edition account, which owns the mint and freeze authorities.
+    pub master_edition: Pubkey,
+}
+
+/// Deploys the Metaplex Token Metadata program to its mainnet address.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `program_path` - Path to the Token Metadata program binary (.so file), as a `&str`, `Path` or `PathBuf`
+///
+/// # Errors
+///
+/// Returns the same errors as [`deploy_program`].
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::deploy_token_metadata_program;
+/// use litesvm::LiteSVM;
+///
+/// let mut litesvm = LiteSVM::new();
+///
+/// // Deploy Token Metadata (this would fail in tests without the .so file)
+/// // deploy_token_metadata_program(&mut litesvm, "tests/fixtures/mpl_token_metadata.so")?;
+/// ```
+pub fn deploy_token_metadata_program(
+    litesvm: &mut LiteSVM,
+    program_path: impl AsRef<Path>,
+) -> Result<(), SolanaKiteError> {
+    deploy_program(litesvm, &mpl_token_metadata::ID, program_path)
+}
+
+/// Creates an NFT: a 0 decimal SPL Token mint with metadata and a master edition, holding one token.
+///
+/// The authority pays for the accounts, is the NFT's update authority and its only
+/// (verified) creator, and receives the token. If `collection` is given, the NFT is
+/// added to that collection and verified, so `authority` must also be the
+/// collection's update authority.
+///
+/// This isn't tested in CI, since the Token Metadata program binary isn't in the
+/// repository. See the [module documentation](self).
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `authority` - Keypair that creates, owns and has authority over the NFT
+/// * `name` - The NFT's name
+/// * `symbol` - The NFT's symbol
+/// * `uri` - URI of the NFT's off-chain JSON metadata
+/// * `collection` - Optional mint of a collection NFT, such as one made with [`create_collection_nft`]
+///
+/// # Returns
+///
+/// Returns the NFT's mint, token account, metadata and master edition addresses.
+///
+/// # Errors
+///
+/// Returns an error if the Token Metadata program isn't loaded, or if any of the
+/// transactions fail, for example because the collection doesn't exist or
+/// `authority` isn't its update authority.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_collection_nft, create_nft, create_wallet, deploy_token_metadata_program};
+/// use litesvm::LiteSVM;
+///
+/// let mut litesvm = LiteSVM::new();
+///
+/// // This would fail in tests without the Token Metadata .so file
+/// // deploy_token_metadata_program(&mut litesvm, "tests/fixtures/mpl_token_metadata.so")?;
+/// // let authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// // let collection = create_collection_nft(&mut litesvm, &authority, "Kites", "KITE", "https://example.com/kites.json")?;
+/// // let nft = create_nft(&mut litesvm, &authority, "Kite #1", "KITE", "https://example.com/1.json", Some(&collection.mint))?;
+/// ```
+pub fn create_nft(
+    litesvm: &mut LiteSVM,
+    authority: &Keypair,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    collection: Option<&Pubkey>,
+) -> Result<Nft, SolanaKiteError> {
+    let nft = create_master_edition_nft(litesvm, authority, name, symbol, uri, collection, None)?;
+
+    if let Some(collection_mint) = collection {
+        let verify_instruction = VerifyCollectionV1Builder::new()
+            .authority(authority.pubkey())
+            .metadata(nft.metadata)
+            .collection_mint(*collection_mint)
+            .collection_metadata(Some(Metadata::find_pda(collection_mint).0))
+            .collection_master_edition(Some(MasterEdition::find_pda(collection_mint).0))
+            .instruction();
+        send_transaction_from_instructions(litesvm, vec![verify_instruction], &[authority], &authority.pubkey())?;
+    }
+
+    Ok(nft)
+}
+
+/// Creates a collection NFT, which other NFTs can belong to.
+///
+/// Like [`create_nft`], but the NFT is a sized collection, and isn't itself in a collection.
+/// It isn't tested in CI either.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `authority` - Keypair that creates, owns and has authority over the collection
+/// * `name` - The collection's name
+/// * `symbol` - The collection's symbol
+/// * `uri` - URI of the collection's off-chain JSON metadata
+///
+/// # Returns
+///
+/// Returns the collection NFT's mint, token account, metadata and master edition addresses.
+///
+/// # Errors
+///
+/// Returns an error if the Token Metadata program isn't loaded, or if any of the transactions fail.
+pub fn create_collection_nft(
+    litesvm: &mut LiteSVM,
+    authority: &Keypair,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Result<Nft, SolanaKiteError> {
+    create_master_edition_nft(
+        litesvm,
+        authority,
+        name,
+        symbol,
+        uri,
+        None,
+        Some(CollectionDetails::V1 { size: 0 }),
+    )
+}
+
+/// Mints one token of a new mint to `authority`, then adds metadata and a master edition.
+fn create_master_edition_nft(
+    litesvm: &mut LiteSVM,
+    authority: &Keypair,
+    name: &str,
+    symbol: &str,
+    uri: &str,
+    collection: Option<&Pubkey>,
+    collection_details: Option<CollectionDetails>,
+) -> Result<Nft, SolanaKiteError> {
+    if !litesvm
+        .get_account(&mpl_token_metadata::ID)
+        .is_some_and(|account| account.executable)
+    {
+        return Err(SolanaKiteError::ProgramDeploymentFailed(
+            "Token Metadata program is not loaded in LiteSVM; deploy it with deploy_token_metadata_program".to_string(),
+        ));
+    }
+
+    let authority_pubkey = authority.pubkey();
+    let mint = create_token_mint(litesvm, authority, 0, None, Some(authority_pubkey), &spl_token::ID)?;
+    let token_account = create_associated_token_account(litesvm, &authority_pubkey, &mint, authority, &spl_token::ID)?;
+    mint_tokens_to_account(litesvm, &mint, &token_account, 1, authority, &spl_token::ID)?;
+
+    let metadata = Metadata::find_pda(&mint).0;
+    let master_edition = MasterEdition::find_pda(&mint).0;
+
+    let data = DataV2 {
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: uri.to_string(),
+        seller_fee_basis_points: 0,
+        creators: Some(vec![Creator {
+            address: authority_pubkey,
+            verified: true,
+            share: 100,
+        }]),
+        collection: collection.map(|key| Collection { verified: false, key: *key }),
+        uses: None,
+    };
+    let mut create_metadata = CreateMetadataAccountV3Builder::new();
+    create_metadata
+        .metadata(metadata)
+        .mint(mint)
+        .mint_authority(authority_pubkey)
+        .payer(authority_pubkey)
+        .update_authority(authority_pubkey, true)
+        .data(data)
+        .is_mutable(true);
+    if let Some(collection_details) = collection_details {
+        create_metadata.collection_details(collection_details);
+    }
+
+    let create_master_edition = CreateMasterEditionV3Builder::new()
+        .edition(master_edition)
+        .mint(mint)
+        .update_authority(authority_pubkey)
+        .mint_authority(authority_pubkey)
+        .payer(authority_pubkey)
+        .metadata(metadata)
+        .max_supply(0)
+        .instruction();
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![create_metadata.instruction(), create_master_edition],
+        &[authority],
+        &authority_pubkey,
+    )?;
+
+    Ok(Nft {
+        mint,
+        token_account,
+        metadata,
+        master_edition,
+    })
+}
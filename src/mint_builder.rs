@@ -0,0 +1,360 @@
+//! A builder for creating token mints with Token-2022 extensions.
+
+use crate::error::SolanaKiteError;
+use crate::token::check_token_program;
+use crate::transaction::send_transaction_from_instructions;
+use litesvm::LiteSVM;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use spl_token_2022::extension::{
+    default_account_state, interest_bearing_mint, metadata_pointer, transfer_fee, transfer_hook,
+    ExtensionType,
+};
+use spl_token_2022::instruction as token_instruction;
+use spl_token_2022::state::{AccountState, Mint};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+/// Token-2022 stores each extension after a 2-byte type and a 2-byte length.
+const EXTENSION_HEADER_LEN: usize = 4;
+
+/// Creates a token mint, optionally with Token-2022 extensions.
+///
+/// Each extension method enables one extension. When the mint is created, the builder
+/// sizes the mint account for the enabled extensions, initializes each extension, then
+/// initializes the mint, then its token metadata, which is the order Token-2022 requires.
+/// The mint authority pays for the mint and is also used as the authority of any
+/// extension that has one, such as the transfer fee config authority.
+///
+/// The builder uses Token-2022 unless another token program is chosen with
+/// [`token_program`](Self::token_program).
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_wallet, MintBuilder};
+/// use litesvm::LiteSVM;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+///
+/// let mint = MintBuilder::new(&mint_authority, 6)
+///     .transfer_fee(50, 5_000) // 0.5%, at most 5,000 base units per transfer
+///     .metadata("Kite", "KITE", "https://example.com/kite.json")
+///     .create(&mut litesvm)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MintBuilder<'a> {
+    mint_authority: &'a Keypair,
+    decimals: u8,
+    mint: Option<Pubkey>,
+    token_program: Pubkey,
+    freeze_authority: Option<Pubkey>,
+    transfer_fee: Option<(u16, u64)>,
+    interest_rate: Option<i16>,
+    non_transferable: bool,
+    permanent_delegate: Option<Pubkey>,
+    close_authority: Option<Pubkey>,
+    default_account_state: Option<AccountState>,
+    metadata: Option<(String, String, String)>,
+    transfer_hook_program: Option<Pubkey>,
+}
+
+impl<'a> MintBuilder<'a> {
+    /// Creates a builder for a Token-2022 mint with this mint authority and number of decimals.
+    pub fn new(mint_authority: &'a Keypair, decimals: u8) -> Self {
+        Self {
+            mint_authority,
+            decimals,
+            mint: None,
+            token_program: spl_token_2022::ID,
+            freeze_authority: None,
+            transfer_fee: None,
+            interest_rate: None,
+            non_transferable: false,
+            permanent_delegate: None,
+            close_authority: None,
+            default_account_state: None,
+            metadata: None,
+            transfer_hook_program: None,
+        }
+    }
+
+    /// Creates the mint at this address instead of a generated one.
+    pub fn mint(mut self, mint: Pubkey) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// Sets the token program that owns the mint. Extensions need Token-2022.
+    pub fn token_program(mut self, token_program: &Pubkey) -> Self {
+        self.token_program = *token_program;
+        self
+    }
+
+    /// Sets the authority that can freeze token accounts of this mint.
+    pub fn freeze_authority(mut self, freeze_authority: Pubkey) -> Self {
+        self.freeze_authority = Some(freeze_authority);
+        self
+    }
+
+    /// Charges a fee of `basis_points` on every transfer, capped at `maximum_fee` base units.
+    pub fn transfer_fee(mut self, basis_points: u16, maximum_fee: u64) -> Self {
+        self.transfer_fee = Some((basis_points, maximum_fee));
+        self
+    }
+
+    /// Makes the mint accrue interest at `rate_basis_points` a year, for display purposes.
+    pub fn interest_bearing(mut self, rate_basis_points: i16) -> Self {
+        self.interest_rate = Some(rate_basis_points);
+        self
+    }
+
+    /// Stops tokens of this mint being transferred.
+    pub fn non_transferable(mut self) -> Self {
+        self.non_transferable = true;
+        self
+    }
+
+    /// Lets `delegate` transfer or burn tokens from any account of this mint.
+    pub fn permanent_delegate(mut self, delegate: Pubkey) -> Self {
+        self.permanent_delegate = Some(delegate);
+        self
+    }
+
+    /// Lets `close_authority` close the mint once its supply is zero.
+    pub fn close_authority(mut self, close_authority: Pubkey) -> Self {
+        self.close_authority = Some(close_authority);
+        self
+    }
+
+    /// Sets the state new token accounts start in. `AccountState::Frozen` needs a freeze authority.
+    pub fn default_account_state(mut self, state: AccountState) -> Self {
+        self.default_account_state = Some(state);
+        self
+    }
+
+    /// Stores token metadata in the mint account, with a metadata pointer to the mint itself.
+    pub fn metadata(mut self, name: impl Into<String>, symbol: impl Into<String>, uri: impl Into<String>) -> Self {
+        self.metadata = Some((name.into(), symbol.into(), uri.into()));
+        self
+    }
+
+    /// Calls `program_id` on every transfer, through the transfer hook interface.
+    pub fn transfer_hook(mut self, program_id: Pubkey) -> Self {
+        self.transfer_hook_program = Some(program_id);
+        self
+    }
+
+    /// Returns the extensions the mint account is created with.
+    pub fn extension_types(&self) -> Vec<ExtensionType> {
+        let mut extension_types = Vec::new();
+        if self.transfer_fee.is_some() {
+            extension_types.push(ExtensionType::TransferFeeConfig);
+        }
+        if self.interest_rate.is_some() {
+            extension_types.push(ExtensionType::InterestBearingConfig);
+        }
+        if self.non_transferable {
+            extension_types.push(ExtensionType::NonTransferable);
+        }
+        if self.permanent_delegate.is_some() {
+            extension_types.push(ExtensionType::PermanentDelegate);
+        }
+        if self.close_authority.is_some() {
+            extension_types.push(ExtensionType::MintCloseAuthority);
+        }
+        if self.default_account_state.is_some() {
+            extension_types.push(ExtensionType::DefaultAccountState);
+        }
+        if self.metadata.is_some() {
+            extension_types.push(ExtensionType::MetadataPointer);
+        }
+        if self.transfer_hook_program.is_some() {
+            extension_types.push(ExtensionType::TransferHook);
+        }
+        extension_types
+    }
+
+    /// Returns the size of the mint account once it's fully initialized, including token metadata.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the size overflows.
+    pub fn account_size(&self) -> Result<usize, SolanaKiteError> {
+        Ok(self.size_before_metadata()? + self.metadata_size()?)
+    }
+
+    /// Returns the instructions that initialize the extensions, the mint and its token
+    /// metadata, in the order Token-2022 requires. The mint account must already exist,
+    /// sized for [`extension_types`](Self::extension_types) and owned by the token program.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if extensions are enabled for a token program other than
+    /// Token-2022, or an instruction can't be built.
+    pub fn instructions(&self, mint: &Pubkey) -> Result<Vec<Instruction>, SolanaKiteError> {
+        let extension_types = self.extension_types();
+        if !extension_types.is_empty() && self.token_program != spl_token_2022::ID {
+            return Err(SolanaKiteError::TokenOperationFailed(format!(
+                "Mint extensions {:?} need the Token-2022 program, not {}",
+                extension_types, self.token_program
+            )));
+        }
+
+        let token_program = &self.token_program;
+        let authority = self.mint_authority.pubkey();
+        let mut instructions = Vec::new();
+
+        // Extensions are initialized before the mint itself
+        if let Some((basis_points, maximum_fee)) = self.transfer_fee {
+            instructions.push(
+                transfer_fee::instruction::initialize_transfer_fee_config(
+                    token_program,
+                    mint,
+                    Some(&authority),
+                    Some(&authority),
+                    basis_points,
+                    maximum_fee,
+                )
+                .map_err(|e| instruction_error("transfer fee config", e))?,
+            );
+        }
+        if let Some(rate) = self.interest_rate {
+            instructions.push(
+                interest_bearing_mint::instruction::initialize(token_program, mint, Some(authority), rate)
+                    .map_err(|e| instruction_error("interest-bearing config", e))?,
+            );
+        }
+        if self.non_transferable {
+            instructions.push(
+                token_instruction::initialize_non_transferable_mint(token_program, mint)
+                    .map_err(|e| instruction_error("non-transferable", e))?,
+            );
+        }
+        if let Some(delegate) = &self.permanent_delegate {
+            instructions.push(
+                token_instruction::initialize_permanent_delegate(token_program, mint, delegate)
+                    .map_err(|e| instruction_error("permanent delegate", e))?,
+            );
+        }
+        if let Some(close_authority) = &self.close_authority {
+            instructions.push(
+                token_instruction::initialize_mint_close_authority(token_program, mint, Some(close_authority))
+                    .map_err(|e| instruction_error("mint close authority", e))?,
+            );
+        }
+        if let Some(state) = &self.default_account_state {
+            instructions.push(
+                default_account_state::instruction::initialize_default_account_state(token_program, mint, state)
+                    .map_err(|e| instruction_error("default account state", e))?,
+            );
+        }
+        if self.metadata.is_some() {
+            instructions.push(
+                metadata_pointer::instruction::initialize(token_program, mint, Some(authority), Some(*mint))
+                    .map_err(|e| instruction_error("metadata pointer", e))?,
+            );
+        }
+        if let Some(program_id) = self.transfer_hook_program {
+            instructions.push(
+                transfer_hook::instruction::initialize(token_program, mint, Some(authority), Some(program_id))
+                    .map_err(|e| instruction_error("transfer hook", e))?,
+            );
+        }
+
+        instructions.push(
+            token_instruction::initialize_mint(
+                token_program,
+                mint,
+                &authority,
+                self.freeze_authority.as_ref(),
+                self.decimals,
+            )
+            .map_err(|e| instruction_error("mint", e))?,
+        );
+
+        // Token metadata can only be written once the mint is initialized
+        if let Some((name, symbol, uri)) = &self.metadata {
+            instructions.push(spl_token_metadata_interface::instruction::initialize(
+                token_program,
+                mint,
+                &authority,
+                mint,
+                &authority,
+                name.clone(),
+                symbol.clone(),
+                uri.clone(),
+            ));
+        }
+
+        Ok(instructions)
+    }
+
+    /// Creates and initializes the mint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token program isn't loaded, an extension isn't
+    /// supported by the token program, or initialization fails.
+    pub fn create(self, litesvm: &mut LiteSVM) -> Result<Pubkey, SolanaKiteError> {
+        check_token_program(litesvm, &self.token_program)?;
+
+        let mint = self.mint.unwrap_or(Pubkey::new_unique());
+        let instructions = self.instructions(&mint)?;
+
+        // Token-2022 grows the account itself when it writes the token metadata, so the
+        // account starts without room for it but with enough lamports to cover it
+        let space = self.size_before_metadata()?;
+        let rent = litesvm.minimum_balance_for_rent_exemption(self.account_size()?);
+
+        litesvm
+            .set_account(
+                mint,
+                solana_account::Account {
+                    lamports: rent,
+                    data: vec![0u8; space],
+                    owner: self.token_program,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create mint account: {:?}", e)))?;
+
+        send_transaction_from_instructions(
+            litesvm,
+            instructions,
+            &[self.mint_authority],
+            &self.mint_authority.pubkey(),
+        )?;
+
+        Ok(mint)
+    }
+
+    fn size_before_metadata(&self) -> Result<usize, SolanaKiteError> {
+        ExtensionType::try_calculate_account_len::<Mint>(&self.extension_types())
+            .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to calculate mint size: {:?}", e)))
+    }
+
+    fn metadata_size(&self) -> Result<usize, SolanaKiteError> {
+        let Some((name, symbol, uri)) = &self.metadata else {
+            return Ok(0);
+        };
+        let metadata = TokenMetadata {
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            ..Default::default()
+        };
+        let metadata_len = borsh::object_length(&metadata)
+            .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to calculate token metadata size: {}", e)))?;
+        Ok(EXTENSION_HEADER_LEN + metadata_len)
+    }
+}
+
+fn instruction_error(name: &str, error: impl std::fmt::Debug) -> SolanaKiteError {
+    SolanaKiteError::TokenOperationFailed(format!("Failed to create initialize {} instruction: {:?}", name, error))
+}
@@ -1,9 +1,19 @@
 //! Program deployment utilities for Solana programs.
 
 use crate::error::SolanaKiteError;
+use crate::transaction::send_transaction_from_instructions;
 use litesvm::LiteSVM;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_loader_v3_interface::instruction::{
+    extend_program_checked, set_upgrade_authority as set_upgrade_authority_instruction, upgrade,
+};
+use solana_loader_v3_interface::{get_program_data_address, state::UpgradeableLoaderState};
+use solana_program::clock::Clock;
 use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
 
 /// Deploys a program to the LiteSVM test environment.
 ///
@@ -15,7 +25,7 @@ use std::fs;
 ///
 /// * `litesvm` - Mutable reference to the LiteSVM instance
 /// * `program_id` - The public key where the program should be deployed
-/// * `program_path` - Path to the compiled program binary (.so file)
+/// * `program_path` - Path to the compiled program binary (.so file), as a `&str`, `Path` or `PathBuf`
 ///
 /// # Returns
 ///
@@ -33,27 +43,67 @@ use std::fs;
 /// use solana_kite::deploy_program;
 /// use litesvm::LiteSVM;
 /// use solana_pubkey::Pubkey;
+/// use std::path::PathBuf;
 ///
 /// let mut litesvm = LiteSVM::new();
 /// let program_id = Pubkey::new_unique();
 /// 
 /// // Deploy a program (this would fail in tests without an actual .so file)
 /// // deploy_program(&mut litesvm, &program_id, "./target/deploy/my_program.so")?;
+///
+/// // A path relative to the crate, so it works from any working directory
+/// let program_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/deploy/my_program.so");
+/// // deploy_program(&mut litesvm, &program_id, program_path)?;
 /// ```
 pub fn deploy_program(
     litesvm: &mut LiteSVM,
     program_id: &Pubkey,
-    program_path: &str,
+    program_path: impl AsRef<Path>,
+) -> Result<(), SolanaKiteError> {
+    let program_bytes = read_program_binary(program_path.as_ref())?;
+    deploy_program_from_bytes(litesvm, program_id, &program_bytes)
+}
+
+/// Deploys a program from a binary already in memory.
+///
+/// Like [`deploy_program`], but takes the program binary itself, so tests can embed
+/// it with `include_bytes!` and run from any working directory.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `program_id` - The public key where the program should be deployed
+/// * `program_bytes` - The compiled program binary
+///
+/// # Errors
+///
+/// This function will return an error if the program binary isn't a valid
+/// program, or the program deployment to LiteSVM fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::deploy_program_from_bytes;
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+///
+/// let mut litesvm = LiteSVM::new();
+/// let program_id = Pubkey::new_unique();
+///
+/// // Embed the program in the test binary (this would fail to compile without an actual .so file)
+/// // deploy_program_from_bytes(&mut litesvm, &program_id, include_bytes!("../target/deploy/my_program.so"))?;
+/// ```
+pub fn deploy_program_from_bytes(
+    litesvm: &mut LiteSVM,
+    program_id: &Pubkey,
+    program_bytes: &[u8],
 ) -> Result<(), SolanaKiteError> {
-    let program_bytes = fs::read(program_path)
-        .map_err(|e| SolanaKiteError::ProgramDeploymentFailed(format!("Failed to read program binary at {}: {}", program_path, e)))?;
-    
     litesvm
         .set_account(
             *program_id,
             solana_account::Account {
                 lamports: litesvm.minimum_balance_for_rent_exemption(program_bytes.len()),
-                data: program_bytes,
+                data: program_bytes.to_vec(),
                 owner: solana_program::bpf_loader::ID,
                 executable: true,
                 rent_epoch: 0,
@@ -62,4 +112,496 @@ pub fn deploy_program(
         .map_err(|e| SolanaKiteError::ProgramDeploymentFailed(format!("Failed to deploy program: {:?}", e)))?;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Deploys every program in an Anchor workspace.
+///
+/// Reads the program names from the `[programs.localnet]` table in the workspace's
+/// `Anchor.toml`, then deploys each program with [`deploy_program`], from
+/// `target/deploy/<name>.so` at the address of `target/deploy/<name>-keypair.json`,
+/// the same files `anchor build` and `anchor deploy` use.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `workspace_root` - Path to the directory containing `Anchor.toml`
+///
+/// # Returns
+///
+/// Returns each program's name, as written in `Anchor.toml`, mapped to its program ID.
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - `Anchor.toml` cannot be read or parsed, or doesn't list any localnet programs
+/// - A program's keypair file or binary cannot be read
+/// - A program deployment to LiteSVM fails
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::deploy_anchor_workspace;
+/// use litesvm::LiteSVM;
+///
+/// let mut litesvm = LiteSVM::new();
+///
+/// // Deploy every program after `anchor build` (this would fail in tests without an Anchor workspace)
+/// // let program_ids = deploy_anchor_workspace(&mut litesvm, env!("CARGO_MANIFEST_DIR"))?;
+/// // let escrow_program_id = program_ids["escrow"];
+/// ```
+pub fn deploy_anchor_workspace(
+    litesvm: &mut LiteSVM,
+    workspace_root: impl AsRef<Path>,
+) -> Result<HashMap<String, Pubkey>, SolanaKiteError> {
+    let workspace_root = workspace_root.as_ref();
+    let anchor_toml_path = workspace_root.join("Anchor.toml");
+    let anchor_toml = fs::read_to_string(&anchor_toml_path).map_err(|e| {
+        SolanaKiteError::ProgramDeploymentFailed(format!("Failed to read {}: {}", anchor_toml_path.display(), e))
+    })?;
+    let program_names = parse_anchor_program_names(&anchor_toml).map_err(|e| {
+        SolanaKiteError::ProgramDeploymentFailed(format!("Failed to parse {}: {}", anchor_toml_path.display(), e))
+    })?;
+    if program_names.is_empty() {
+        return Err(SolanaKiteError::ProgramDeploymentFailed(format!(
+            "{} doesn't list any programs under [programs.localnet]",
+            anchor_toml_path.display()
+        )));
+    }
+
+    let deploy_directory = workspace_root.join("target").join("deploy");
+    let mut program_ids = HashMap::new();
+    for program_name in program_names {
+        // Anchor names build artifacts after the crate's library name, which uses underscores
+        let artifact_name = program_name.replace('-', "_");
+        let keypair_path = deploy_directory.join(format!("{}-keypair.json", artifact_name));
+        let program_id = read_keypair_file(&keypair_path)
+            .map_err(|e| {
+                SolanaKiteError::ProgramDeploymentFailed(format!(
+                    "Failed to read program keypair at {}: {}",
+                    keypair_path.display(),
+                    e
+                ))
+            })?
+            .pubkey();
+
+        deploy_program(litesvm, &program_id, deploy_directory.join(format!("{}.so", artifact_name)))?;
+        program_ids.insert(program_name, program_id);
+    }
+
+    Ok(program_ids)
+}
+
+/// Deploys a program under the upgradeable BPF loader, with an upgrade authority.
+///
+/// Unlike [`deploy_program`], this creates both the Program account and its
+/// ProgramData account, the way `solana program deploy` does, so programs that read
+/// their ProgramData account or check their upgrade authority can be tested, and
+/// the program can later be upgraded with [`upgrade_program`].
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `program_id` - The public key where the program should be deployed
+/// * `program_path` - Path to the compiled program binary (.so file), as a `&str`, `Path` or `PathBuf`
+/// * `upgrade_authority` - The program's upgrade authority, or None for an immutable program
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The program binary file cannot be read
+/// - The program binary isn't a valid program, or the deployment to LiteSVM fails
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_wallet, deploy_upgradeable_program};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let program_id = Pubkey::new_unique();
+/// let upgrade_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+///
+/// // Use the memo program LiteSVM ships with as the program binary
+/// let memo_program: Pubkey = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse()?;
+/// let program_path = std::env::temp_dir().join(format!("{}.so", Pubkey::new_unique()));
+/// std::fs::write(&program_path, litesvm.get_account(&memo_program).unwrap().data)?;
+///
+/// deploy_upgradeable_program(&mut litesvm, &program_id, &program_path, Some(&upgrade_authority.pubkey()))?;
+/// assert!(litesvm.get_account(&program_id).unwrap().executable);
+/// # std::fs::remove_file(program_path)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn deploy_upgradeable_program(
+    litesvm: &mut LiteSVM,
+    program_id: &Pubkey,
+    program_path: impl AsRef<Path>,
+    upgrade_authority: Option<&Pubkey>,
+) -> Result<(), SolanaKiteError> {
+    let program_bytes = read_program_binary(program_path.as_ref())?;
+    let programdata_address = get_program_data_address(program_id);
+
+    // The ProgramData account must exist before the Program account, which LiteSVM loads the program from
+    let programdata_state = UpgradeableLoaderState::ProgramData {
+        slot: litesvm.get_sysvar::<Clock>().slot,
+        upgrade_authority_address: upgrade_authority.copied(),
+    };
+    let programdata = loader_account_data(
+        &programdata_state,
+        UpgradeableLoaderState::size_of_programdata_metadata(),
+        &program_bytes,
+    )?;
+    set_loader_account(litesvm, &programdata_address, programdata, false)?;
+
+    let program_state = UpgradeableLoaderState::Program { programdata_address };
+    let program = loader_account_data(&program_state, UpgradeableLoaderState::size_of_program(), &[])?;
+    set_loader_account(litesvm, program_id, program, true)
+}
+
+/// Upgrades a program deployed with [`deploy_upgradeable_program`] to a new binary.
+///
+/// The new binary is written to a buffer account, then the upgradeable loader's
+/// `Upgrade` instruction is sent, signed by the upgrade authority, so the upgrade
+/// authority is checked just as it is on a real cluster. If the new binary is bigger,
+/// the ProgramData account is first grown with the loader's `ExtendProgramChecked`
+/// instruction, paid for by the upgrade authority. The loader doesn't allow a program
+/// to be deployed, extended and upgraded in the same slot, so if that would happen,
+/// LiteSVM is warped forward one slot.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `program_id` - The public key of the program to upgrade
+/// * `program_path` - Path to the new program binary (.so file), as a `&str`, `Path` or `PathBuf`
+/// * `upgrade_authority` - The program's upgrade authority, which signs and pays for the upgrade
+///
+/// # Errors
+///
+/// This function will return an error if:
+/// - The program binary file cannot be read
+/// - The program isn't an upgradeable program
+/// - The upgrade transaction fails, for example because `upgrade_authority` isn't
+///   the program's upgrade authority, or the program is immutable
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_wallet, deploy_upgradeable_program, upgrade_program};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let program_id = Pubkey::new_unique();
+/// let upgrade_authority = create_wallet(&mut litesvm, 10_000_000_000)?;
+///
+/// // Use the memo and token programs LiteSVM ships with as the old and new program binaries
+/// let memo_program: Pubkey = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse()?;
+/// let old_program_path = std::env::temp_dir().join(format!("{}.so", Pubkey::new_unique()));
+/// std::fs::write(&old_program_path, litesvm.get_account(&memo_program).unwrap().data)?;
+/// let new_program_path = std::env::temp_dir().join(format!("{}.so", Pubkey::new_unique()));
+/// std::fs::write(&new_program_path, litesvm.get_account(&spl_token::ID).unwrap().data)?;
+///
+/// deploy_upgradeable_program(&mut litesvm, &program_id, &old_program_path, Some(&upgrade_authority.pubkey()))?;
+/// upgrade_program(&mut litesvm, &program_id, &new_program_path, &upgrade_authority)?;
+/// # std::fs::remove_file(old_program_path)?;
+/// # std::fs::remove_file(new_program_path)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn upgrade_program(
+    litesvm: &mut LiteSVM,
+    program_id: &Pubkey,
+    program_path: impl AsRef<Path>,
+    upgrade_authority: &Keypair,
+) -> Result<(), SolanaKiteError> {
+    let program_bytes = read_program_binary(program_path.as_ref())?;
+    let (programdata_account, _, _) = get_programdata(litesvm, program_id)?;
+
+    // The loader requires the ProgramData account to have room for the new binary
+    let programdata_len = UpgradeableLoaderState::size_of_programdata(program_bytes.len());
+    if programdata_account.data.len() < programdata_len {
+        let additional_bytes = u32::try_from(programdata_len - programdata_account.data.len()).map_err(|_| {
+            SolanaKiteError::ProgramDeploymentFailed(format!("{} is too large", program_path.as_ref().display()))
+        })?;
+        warp_past_deployment_slot(litesvm, program_id)?;
+        let extend_instruction = extend_program_checked(
+            program_id,
+            &upgrade_authority.pubkey(),
+            Some(&upgrade_authority.pubkey()),
+            additional_bytes,
+        );
+        send_transaction_from_instructions(
+            litesvm,
+            vec![extend_instruction],
+            &[upgrade_authority],
+            &upgrade_authority.pubkey(),
+        )?;
+    }
+
+    warp_past_deployment_slot(litesvm, program_id)?;
+
+    let buffer_address = Pubkey::new_unique();
+    let buffer_state = UpgradeableLoaderState::Buffer {
+        authority_address: Some(upgrade_authority.pubkey()),
+    };
+    let buffer = loader_account_data(&buffer_state, UpgradeableLoaderState::size_of_buffer_metadata(), &program_bytes)?;
+    set_loader_account(litesvm, &buffer_address, buffer, false)?;
+
+    let upgrade_instruction = upgrade(
+        program_id,
+        &buffer_address,
+        &upgrade_authority.pubkey(),
+        &upgrade_authority.pubkey(),
+    );
+    send_transaction_from_instructions(
+        litesvm,
+        vec![upgrade_instruction],
+        &[upgrade_authority],
+        &upgrade_authority.pubkey(),
+    )?;
+
+    Ok(())
+}
+
+/// Changes, or removes, the upgrade authority of an upgradeable program.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `program_id` - The public key of the upgradeable program
+/// * `new_authority` - The new upgrade authority, or None to make the program immutable
+/// * `current_authority` - The current upgrade authority, which signs and pays for the transaction
+///
+/// # Errors
+///
+/// This function will return an error if the transaction fails, for example
+/// because `current_authority` isn't the program's upgrade authority.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_wallet, deploy_upgradeable_program, get_upgrade_authority, set_upgrade_authority};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let program_id = Pubkey::new_unique();
+/// let upgrade_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let new_authority = Pubkey::new_unique();
+///
+/// // Use the memo program LiteSVM ships with as the program binary
+/// let memo_program: Pubkey = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse()?;
+/// let program_path = std::env::temp_dir().join(format!("{}.so", Pubkey::new_unique()));
+/// std::fs::write(&program_path, litesvm.get_account(&memo_program).unwrap().data)?;
+/// deploy_upgradeable_program(&mut litesvm, &program_id, &program_path, Some(&upgrade_authority.pubkey()))?;
+///
+/// set_upgrade_authority(&mut litesvm, &program_id, Some(&new_authority), &upgrade_authority)?;
+/// assert_eq!(get_upgrade_authority(&litesvm, &program_id)?, Some(new_authority));
+/// # std::fs::remove_file(program_path)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_upgrade_authority(
+    litesvm: &mut LiteSVM,
+    program_id: &Pubkey,
+    new_authority: Option<&Pubkey>,
+    current_authority: &Keypair,
+) -> Result<(), SolanaKiteError> {
+    let set_authority_instruction =
+        set_upgrade_authority_instruction(program_id, &current_authority.pubkey(), new_authority);
+    send_transaction_from_instructions(
+        litesvm,
+        vec![set_authority_instruction],
+        &[current_authority],
+        &current_authority.pubkey(),
+    )?;
+    Ok(())
+}
+
+/// Gets the upgrade authority of an upgradeable program.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `program_id` - The public key of the upgradeable program
+///
+/// # Returns
+///
+/// Returns the upgrade authority, or None if the program is immutable.
+///
+/// # Errors
+///
+/// This function will return an error if the program isn't an upgradeable program.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_wallet, deploy_upgradeable_program, get_upgrade_authority};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let program_id = Pubkey::new_unique();
+/// let upgrade_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+///
+/// // Use the memo program LiteSVM ships with as the program binary
+/// let memo_program: Pubkey = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse()?;
+/// let program_path = std::env::temp_dir().join(format!("{}.so", Pubkey::new_unique()));
+/// std::fs::write(&program_path, litesvm.get_account(&memo_program).unwrap().data)?;
+/// deploy_upgradeable_program(&mut litesvm, &program_id, &program_path, Some(&upgrade_authority.pubkey()))?;
+///
+/// assert_eq!(get_upgrade_authority(&litesvm, &program_id)?, Some(upgrade_authority.pubkey()));
+/// # std::fs::remove_file(program_path)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn get_upgrade_authority(litesvm: &LiteSVM, program_id: &Pubkey) -> Result<Option<Pubkey>, SolanaKiteError> {
+    let (_, _, upgrade_authority) = get_programdata(litesvm, program_id)?;
+    Ok(upgrade_authority)
+}
+
+/// Warps LiteSVM forward one slot if the program was deployed, upgraded or extended in the current slot,
+/// which the loader doesn't allow a program to be again.
+fn warp_past_deployment_slot(litesvm: &mut LiteSVM, program_id: &Pubkey) -> Result<(), SolanaKiteError> {
+    let (_, deployment_slot, _) = get_programdata(litesvm, program_id)?;
+    if deployment_slot >= litesvm.get_sysvar::<Clock>().slot {
+        litesvm.warp_to_slot(deployment_slot + 1);
+    }
+    Ok(())
+}
+
+fn read_program_binary(program_path: &Path) -> Result<Vec<u8>, SolanaKiteError> {
+    fs::read(program_path).map_err(|e| {
+        SolanaKiteError::ProgramDeploymentFailed(format!(
+            "Failed to read program binary at {}: {}",
+            program_path.display(),
+            e
+        ))
+    })
+}
+
+/// Gets an upgradeable program's ProgramData account, with its last deployment slot and upgrade authority.
+fn get_programdata(
+    litesvm: &LiteSVM,
+    program_id: &Pubkey,
+) -> Result<(solana_account::Account, u64, Option<Pubkey>), SolanaKiteError> {
+    let not_upgradeable =
+        || SolanaKiteError::ProgramDeploymentFailed(format!("{} is not an upgradeable program", program_id));
+
+    let program_account = litesvm.get_account(program_id).ok_or_else(not_upgradeable)?;
+    if program_account.owner != solana_sdk_ids::bpf_loader_upgradeable::ID {
+        return Err(not_upgradeable());
+    }
+    let Ok(UpgradeableLoaderState::Program { programdata_address }) = bincode::deserialize(&program_account.data) else {
+        return Err(not_upgradeable());
+    };
+
+    let programdata_account = litesvm.get_account(&programdata_address).ok_or_else(not_upgradeable)?;
+    let Ok(UpgradeableLoaderState::ProgramData {
+        slot,
+        upgrade_authority_address,
+    }) = bincode::deserialize(&programdata_account.data)
+    else {
+        return Err(not_upgradeable());
+    };
+    Ok((programdata_account, slot, upgrade_authority_address))
+}
+
+/// Serializes upgradeable loader state into its fixed-size header, followed by the program binary.
+fn loader_account_data(
+    state: &UpgradeableLoaderState,
+    metadata_len: usize,
+    program_bytes: &[u8],
+) -> Result<Vec<u8>, SolanaKiteError> {
+    let mut data = vec![0u8; metadata_len + program_bytes.len()];
+    bincode::serialize_into(&mut data[..metadata_len], state)
+        .map_err(|e| SolanaKiteError::ProgramDeploymentFailed(format!("Failed to serialize loader state: {}", e)))?;
+    data[metadata_len..].copy_from_slice(program_bytes);
+    Ok(data)
+}
+
+fn set_loader_account(
+    litesvm: &mut LiteSVM,
+    address: &Pubkey,
+    data: Vec<u8>,
+    executable: bool,
+) -> Result<(), SolanaKiteError> {
+    litesvm
+        .set_account(
+            *address,
+            solana_account::Account {
+                lamports: litesvm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: solana_sdk_ids::bpf_loader_upgradeable::ID,
+                executable,
+                rent_epoch: 0,
+            },
+        )
+        .map_err(|e| SolanaKiteError::ProgramDeploymentFailed(format!("Failed to deploy program: {:?}", e)))
+}
+
+/// Returns the program names in an `Anchor.toml`'s `[programs.localnet]` table.
+fn parse_anchor_program_names(anchor_toml: &str) -> Result<Vec<String>, String> {
+    let config: toml::Table = anchor_toml.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+    let Some(programs) = config.get("programs") else {
+        return Ok(Vec::new());
+    };
+    let clusters = programs
+        .as_table()
+        .ok_or_else(|| "[programs] should be a table of clusters".to_string())?;
+    // Only the localnet programs are built for local testing, other clusters may list programs that aren't in target/deploy
+    let Some(localnet_programs) = clusters.get("localnet") else {
+        return Ok(Vec::new());
+    };
+    let localnet_programs = localnet_programs
+        .as_table()
+        .ok_or_else(|| "[programs.localnet] should be a table of program IDs".to_string())?;
+    let program_names = localnet_programs.keys().cloned().collect();
+    Ok(program_names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_anchor_program_names() {
+        let anchor_toml = r#"
+[toolchain]
+anchor_version = "0.32.0"
+
+[features]
+resolution = true
+
+[programs.localnet]
+escrow = "qbuMdeYxYJXBjU6C6qFKjZKjXmrU83eDQomHdrch826"
+token_vault = "8jR5GeNzeweq35Uo84kGP3v1NcBaZWH5u62k7PxN4T2y"
+
+[programs.devnet]
+escrow = "qbuMdeYxYJXBjU6C6qFKjZKjXmrU83eDQomHdrch826"
+devnet-only = "8jR5GeNzeweq35Uo84kGP3v1NcBaZWH5u62k7PxN4T2y"
+
+[provider]
+cluster = "localnet"
+wallet = "~/.config/solana/id.json"
+"#;
+        let mut program_names = parse_anchor_program_names(anchor_toml).unwrap();
+        program_names.sort();
+        assert_eq!(program_names, vec!["escrow", "token_vault"]);
+
+        assert_eq!(parse_anchor_program_names("[provider]\ncluster = \"localnet\"\n").unwrap(), Vec::<String>::new());
+        assert!(parse_anchor_program_names("programs = 5").is_err());
+        assert!(parse_anchor_program_names("[programs]\nlocalnet = 5").is_err());
+        assert_eq!(parse_anchor_program_names("[programs.devnet]\nescrow = \"11111111111111111111111111111111\"\n").unwrap(), Vec::<String>::new());
+        assert!(parse_anchor_program_names("[programs.localnet\n").is_err());
+    }
+}
@@ -1,20 +1,47 @@
 //! Token operations for SPL tokens on Solana.
+//!
+//! Every helper works with both the original SPL Token program and Token-2022. Pass
+//! `&spl_token::ID` or `&spl_token_2022::ID` as the `token_program` to choose between
+//! them. `LiteSVM::new()` loads both programs.
 
 use crate::error::SolanaKiteError;
+use crate::mint_builder::MintBuilder;
+use crate::transaction::send_transaction_from_instructions;
+use bytemuck::Pod;
 use litesvm::LiteSVM;
 use solana_keypair::Keypair;
-use solana_message::Message;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
 use solana_pubkey::Pubkey;
 use solana_signer::Signer;
-use solana_transaction::Transaction;
-use spl_associated_token_account::instruction::create_associated_token_account as create_ata_instruction;
-use spl_token::instruction::mint_to;
+use spl_associated_token_account::instruction::{
+    create_associated_token_account as create_ata_instruction,
+    create_associated_token_account_idempotent as create_ata_idempotent_instruction,
+};
+use spl_token_2022::extension::default_account_state::DefaultAccountState;
+use spl_token_2022::extension::immutable_owner::ImmutableOwner;
+use spl_token_2022::extension::mint_close_authority::MintCloseAuthority;
+use spl_token_2022::extension::non_transferable::NonTransferableAccount;
+use spl_token_2022::extension::pausable::PausableAccount;
+use spl_token_2022::extension::transfer_fee::TransferFeeAmount;
+use spl_token_2022::extension::transfer_hook::TransferHookAccount;
+use spl_token_2022::extension::{
+    BaseState, BaseStateWithExtensions, BaseStateWithExtensionsMut, Extension, ExtensionType,
+    StateWithExtensions, StateWithExtensionsMut,
+};
+use spl_token_2022::instruction::{
+    approve, approve_checked, burn, close_account, freeze_account, mint_to, revoke, set_authority,
+    initialize_account3, sync_native, thaw_account, transfer_checked, AuthorityType, MAX_SIGNERS,
+};
+use spl_token_2022::state::{Account, AccountState, Mint, Multisig};
+use spl_token_metadata_interface::state::TokenMetadata;
 
 /// Creates a new SPL token mint with the specified mint authority and decimals.
 ///
 /// This function creates a new token mint account with proper rent exemption and 
-/// initializes it as an SPL token mint. You can optionally specify a custom mint
-/// address, or let the function generate a unique one.
+/// initializes it as a mint of the chosen token program. You can optionally specify
+/// a custom mint address, or let the function generate a unique one. Use
+/// [`MintBuilder`] for a mint with Token-2022 extensions.
 ///
 /// # Arguments
 ///
@@ -22,6 +49,8 @@ use spl_token::instruction::mint_to;
 /// * `mint_authority` - Keypair that will have authority to mint tokens
 /// * `decimals` - Number of decimal places for the token (0-9)
 /// * `mint` - Optional custom public key for the mint. If None, a unique address will be generated
+/// * `freeze_authority` - Optional public key that will have authority to freeze token accounts
+/// * `token_program` - The token program that will own the mint: `spl_token::ID` or `spl_token_2022::ID`
 ///
 /// # Returns
 ///
@@ -29,7 +58,8 @@ use spl_token::instruction::mint_to;
 ///
 /// # Errors
 ///
-/// This function will return an error if the mint creation or initialization fails.
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the mint creation or initialization fails.
 ///
 /// # Example
 ///
@@ -43,53 +73,28 @@ use spl_token::instruction::mint_to;
 /// let mint_authority = Keypair::new();
 /// 
 /// // Create a mint with auto-generated address
-/// let mint_pubkey = create_token_mint(&mut litesvm, &mint_authority, 6, None);
+/// let mint_pubkey = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID);
 /// 
-/// // Or create a mint with a custom address
+/// // Or create a Token-2022 mint with a custom address
 /// let custom_mint = Pubkey::new_unique();
-/// let mint_pubkey = create_token_mint(&mut litesvm, &mint_authority, 6, Some(custom_mint));
+/// let mint_pubkey = create_token_mint(&mut litesvm, &mint_authority, 6, Some(custom_mint), None, &spl_token_2022::ID);
 /// ```
 pub fn create_token_mint(
     litesvm: &mut LiteSVM,
     mint_authority: &Keypair,
     decimals: u8,
     mint: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+    token_program: &Pubkey,
 ) -> Result<Pubkey, SolanaKiteError> {
-    let mint = mint.unwrap_or(Pubkey::new_unique());
-    let rent = litesvm.minimum_balance_for_rent_exemption(82);
-
-    litesvm
-        .set_account(
-            mint,
-            solana_account::Account {
-                lamports: rent,
-                data: vec![0u8; 82],
-                owner: spl_token::ID,
-                executable: false,
-                rent_epoch: 0,
-            },
-        )
-        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create mint account: {:?}", e)))?;
-
-    let initialize_mint_instruction = spl_token::instruction::initialize_mint(
-        &spl_token::ID,
-        &mint,
-        &mint_authority.pubkey(),
-        None,
-        decimals,
-    )
-    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create initialize mint instruction: {:?}", e)))?;
-
-    let message = Message::new(&[initialize_mint_instruction], Some(&mint_authority.pubkey()));
-    let mut transaction = Transaction::new_unsigned(message);
-    let blockhash = litesvm.latest_blockhash();
-    transaction.sign(&[mint_authority], blockhash);
-    
-    litesvm
-        .send_transaction(transaction)
-        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to initialize mint: {:?}", e)))?;
-
-    Ok(mint)
+    let mut builder = MintBuilder::new(mint_authority, decimals).token_program(token_program);
+    if let Some(freeze_authority) = freeze_authority {
+        builder = builder.freeze_authority(freeze_authority);
+    }
+    if let Some(mint) = mint {
+        builder = builder.mint(mint);
+    }
+    builder.create(litesvm)
 }
 
 /// Creates an associated token account for the given owner and mint.
@@ -104,6 +109,7 @@ pub fn create_token_mint(
 /// * `owner` - Public key of the account that will own the token account
 /// * `mint` - Public key of the token mint
 /// * `payer` - Keypair that will pay for the account creation and sign the transaction
+/// * `token_program` - The token program that owns the mint
 ///
 /// # Returns
 ///
@@ -111,7 +117,8 @@ pub fn create_token_mint(
 ///
 /// # Errors
 ///
-/// This function will return an error if the account creation fails.
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the account creation fails.
 ///
 /// # Example
 ///
@@ -126,13 +133,14 @@ pub fn create_token_mint(
 /// let owner_wallet = create_wallet(&mut litesvm, 1_000_000_000)?;
 /// let payer_wallet = create_wallet(&mut litesvm, 1_000_000_000)?;
 /// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
-/// let mint_pubkey = create_token_mint(&mut litesvm, &mint_authority, 6, None)?;
+/// let mint_pubkey = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
 /// 
 /// let token_account = create_associated_token_account(
 ///     &mut litesvm,
 ///     &owner_wallet.pubkey(),
 ///     &mint_pubkey,
 ///     &payer_wallet,
+///     &spl_token::ID,
 /// )?;
 /// # Ok(())
 /// # }
@@ -142,29 +150,390 @@ pub fn create_associated_token_account(
     owner: &Pubkey,
     mint: &Pubkey,
     payer: &Keypair,
+    token_program: &Pubkey,
 ) -> Result<Pubkey, SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+
+    // The associated token account program sizes the account for the mint's extensions
     let associated_token_account =
-        spl_associated_token_account::get_associated_token_address(owner, mint);
+        spl_associated_token_account::get_associated_token_address_with_program_id(owner, mint, token_program);
 
     let create_ata_instruction = create_ata_instruction(
         &payer.pubkey(),
-        &owner,
+        owner,
         mint,
-        &spl_token::id(),
+        token_program,
     );
 
-    let message = Message::new(&[create_ata_instruction], Some(&payer.pubkey()));
-    let mut transaction = Transaction::new_unsigned(message);
-    let blockhash = litesvm.latest_blockhash();
-    transaction.sign(&[payer], blockhash);
-    
-    litesvm
-        .send_transaction(transaction)
-        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create associated token account: {:?}", e)))?;
+    send_transaction_from_instructions(
+        litesvm,
+        vec![create_ata_instruction],
+        &[payer],
+        &payer.pubkey(),
+    )?;
+
+    Ok(associated_token_account)
+}
+
+/// Creates an associated token account, or does nothing if it already exists.
+///
+/// Like [`create_associated_token_account`], but succeeds when the account has
+/// already been created, for example by the program under test.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `owner` - Public key of the account that will own the token account
+/// * `mint` - Public key of the token mint
+/// * `payer` - Keypair that will pay for the account creation and sign the transaction
+/// * `token_program` - The token program that owns the mint
+///
+/// # Returns
+///
+/// Returns the public key of the associated token account.
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the account doesn't exist and can't be created.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_associated_token_account_idempotent, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &owner, 6, None, None, &spl_token::ID)?;
+///
+/// let token_account = create_associated_token_account_idempotent(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+/// let same_account = create_associated_token_account_idempotent(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+/// assert_eq!(token_account, same_account);
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_associated_token_account_idempotent(
+    litesvm: &mut LiteSVM,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    payer: &Keypair,
+    token_program: &Pubkey,
+) -> Result<Pubkey, SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+
+    let associated_token_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(owner, mint, token_program);
+
+    // LiteSVM rejects a transaction identical to one it has already processed, so
+    // move to a new blockhash when the instruction may have been sent before
+    if litesvm.get_account(&associated_token_account).is_some() {
+        litesvm.expire_blockhash();
+    }
+
+    let create_ata_instruction = create_ata_idempotent_instruction(&payer.pubkey(), owner, mint, token_program);
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![create_ata_instruction],
+        &[payer],
+        &payer.pubkey(),
+    )?;
 
     Ok(associated_token_account)
 }
 
+/// Creates a token account that isn't an associated token account.
+///
+/// The account can be at any address, including a PDA, and owned by any account,
+/// including a PDA, so you can set up vaults the way a program would. It's sized for
+/// the extensions the mint requires on its token accounts, then initialized.
+///
+/// Without an `address`, the account is created at a new keypair's address with the
+/// system program's `CreateAccount` instruction, so `payer` pays its rent. An
+/// `address` may be a PDA, which only its program could sign a `CreateAccount` for,
+/// so an account at a given `address` has its rent-exempt lamports set directly in
+/// LiteSVM instead.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `owner` - Public key of the account that will own the token account
+/// * `mint` - Public key of the token mint
+/// * `payer` - Keypair that will sign and pay for the transaction, and the account's rent when `address` is None
+/// * `address` - Optional address for the token account. If None, a unique address will be generated
+/// * `token_program` - The token program that owns the mint
+///
+/// # Returns
+///
+/// Returns the public key of the created token account.
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, if the mint isn't owned by `token_program`, if an account
+/// already exists at `address`, or if the account creation or initialization fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_token_account, create_token_mint, create_wallet, get_pda_and_bump, Seed};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &payer, 6, None, None, &spl_token::ID)?;
+///
+/// // A vault at a PDA, owned by another PDA of the same program
+/// let program_id = Pubkey::new_unique();
+/// let (vault_authority, _) = get_pda_and_bump(&[Seed::from("authority")], &program_id);
+/// let (vault_address, _) = get_pda_and_bump(&[Seed::from("vault"), Seed::from(mint)], &program_id);
+/// let vault = create_token_account(&mut litesvm, &vault_authority, &mint, &payer, Some(vault_address), &spl_token::ID)?;
+/// assert_eq!(vault, vault_address);
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_token_account(
+    litesvm: &mut LiteSVM,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    payer: &Keypair,
+    address: Option<Pubkey>,
+    token_program: &Pubkey,
+) -> Result<Pubkey, SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let mint_state = get_mint(litesvm, mint)?;
+    if mint_state.token_program != *token_program {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "Mint {} is owned by {}, not {}",
+            mint, mint_state.token_program, token_program
+        )));
+    }
+
+    if let Some(address) = address {
+        if litesvm.get_account(&address).is_some() {
+            return Err(SolanaKiteError::TokenOperationFailed(format!(
+                "Account {} already exists",
+                address
+            )));
+        }
+    }
+
+    // Token-2022 initializes the account extensions the mint requires, if there's room for them
+    let extension_types = ExtensionType::get_required_init_account_extensions(&mint_state.extension_types);
+    let space = ExtensionType::try_calculate_account_len::<Account>(&extension_types)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to calculate token account size: {:?}", e)))?;
+    let lamports = litesvm.minimum_balance_for_rent_exemption(space);
+
+    let account_keypair = Keypair::new();
+    let token_account = address.unwrap_or(account_keypair.pubkey());
+    let initialize_account_instruction = initialize_account3(token_program, &token_account, mint, owner)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create initialize_account3 instruction: {:?}", e)))?;
+
+    if address.is_some() {
+        litesvm
+            .set_account(
+                token_account,
+                solana_account::Account {
+                    lamports,
+                    data: vec![0u8; space],
+                    owner: *token_program,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create token account: {:?}", e)))?;
+
+        send_transaction_from_instructions(
+            litesvm,
+            vec![initialize_account_instruction],
+            &[payer],
+            &payer.pubkey(),
+        )?;
+    } else {
+        let create_account_instruction = solana_program::system_instruction::create_account(
+            &payer.pubkey(),
+            &token_account,
+            lamports,
+            space as u64,
+            token_program,
+        );
+
+        send_transaction_from_instructions(
+            litesvm,
+            vec![create_account_instruction, initialize_account_instruction],
+            &[payer, &account_keypair],
+            &payer.pubkey(),
+        )?;
+    }
+
+    Ok(token_account)
+}
+
+/// The authority that signs a token instruction: a single keypair, or a multisig
+/// account along with enough of its signers.
+///
+/// Helpers that take an authority accept a `&Keypair` directly. Use
+/// [`TokenAuthority::multisig`] when the authority is a multisig account created
+/// with [`create_multisig`]. The first signer pays the transaction fee.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{
+///     create_associated_token_account, create_multisig, create_token_mint, create_wallet, create_wallets,
+///     mint_tokens_to_account, set_token_authority, TokenAuthority,
+/// };
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+/// use spl_token_2022::instruction::AuthorityType;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let signers = create_wallets(&mut litesvm, 3, 1_000_000_000)?;
+/// let signer_pubkeys: Vec<_> = signers.iter().map(|signer| signer.pubkey()).collect();
+/// let multisig = create_multisig(&mut litesvm, &signer_pubkeys, 2, &spl_token::ID)?;
+///
+/// // A mint whose mint authority is a 2 of 3 multisig
+/// let mint = create_token_mint(&mut litesvm, &signers[0], 6, None, None, &spl_token::ID)?;
+/// set_token_authority(&mut litesvm, &mint, AuthorityType::MintTokens, Some(&multisig), &signers[0], &spl_token::ID)?;
+///
+/// let recipient = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &recipient.pubkey(), &mint, &recipient, &spl_token::ID)?;
+/// mint_tokens_to_account(
+///     &mut litesvm,
+///     &mint,
+///     &token_account,
+///     1_000_000,
+///     TokenAuthority::multisig(&multisig, &[&signers[0], &signers[2]]),
+///     &spl_token::ID,
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TokenAuthority<'a> {
+    authority: Pubkey,
+    signers: Vec<&'a Keypair>,
+    multisig_signers: Vec<Pubkey>,
+}
+
+impl<'a> TokenAuthority<'a> {
+    /// A multisig authority, signed by `signers`. Pass at least as many signers as the multisig requires.
+    ///
+    /// The first signer pays the transaction fee. Helpers given a multisig authority with
+    /// no signers return [`SolanaKiteError::TokenOperationFailed`].
+    pub fn multisig(multisig: &Pubkey, signers: &[&'a Keypair]) -> Self {
+        Self {
+            authority: *multisig,
+            signers: signers.to_vec(),
+            multisig_signers: signers.iter().map(|signer| signer.pubkey()).collect(),
+        }
+    }
+
+    /// Returns the authority's address: the keypair's public key, or the multisig account.
+    pub fn pubkey(&self) -> Pubkey {
+        self.authority
+    }
+
+    fn signer_pubkeys(&self) -> Vec<&Pubkey> {
+        self.multisig_signers.iter().collect()
+    }
+
+    /// The first signer, who pays for the transaction. A multisig account can't sign.
+    fn fee_payer(&self) -> Result<Pubkey, SolanaKiteError> {
+        self.signers.first().map(|signer| signer.pubkey()).ok_or_else(|| {
+            SolanaKiteError::TokenOperationFailed(format!(
+                "Multisig authority {} has no signers to sign or pay for the transaction",
+                self.authority
+            ))
+        })
+    }
+}
+
+impl<'a> From<&'a Keypair> for TokenAuthority<'a> {
+    fn from(keypair: &'a Keypair) -> Self {
+        Self {
+            authority: keypair.pubkey(),
+            signers: vec![keypair],
+            multisig_signers: Vec::new(),
+        }
+    }
+}
+
+/// Creates an M of N multisig account, which can be used as a mint, freeze or token account authority.
+///
+/// The multisig state is written straight into LiteSVM, so no transaction or payer is needed.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `signers` - Public keys of the multisig's signers, at most 11
+/// * `m` - Number of signers required to sign
+/// * `token_program` - The token program that will own the multisig
+///
+/// # Returns
+///
+/// Returns the public key of the new multisig account.
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, there are more than 11 signers, or `m` is 0 or greater
+/// than the number of signers.
+///
+/// # Example
+///
+/// See [`TokenAuthority`].
+pub fn create_multisig(
+    litesvm: &mut LiteSVM,
+    signers: &[Pubkey],
+    m: u8,
+    token_program: &Pubkey,
+) -> Result<Pubkey, SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+
+    if signers.len() > MAX_SIGNERS || m == 0 || usize::from(m) > signers.len() {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "Invalid multisig: {} of {} signers, with at most {} signers",
+            m,
+            signers.len(),
+            MAX_SIGNERS
+        )));
+    }
+
+    let mut multisig_signers = [Pubkey::default(); MAX_SIGNERS];
+    multisig_signers[..signers.len()].copy_from_slice(signers);
+    let multisig_state = Multisig {
+        m,
+        n: signers.len() as u8,
+        is_initialized: true,
+        signers: multisig_signers,
+    };
+    let mut data = vec![0u8; Multisig::LEN];
+    Multisig::pack(multisig_state, &mut data)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to pack multisig: {:?}", e)))?;
+
+    let multisig = Pubkey::new_unique();
+    litesvm
+        .set_account(
+            multisig,
+            solana_account::Account {
+                lamports: litesvm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: *token_program,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create multisig account: {:?}", e)))?;
+
+    Ok(multisig)
+}
+
 /// Mints tokens to a specified token account.
 ///
 /// This function creates a mint_to instruction and sends it as a transaction.
@@ -176,11 +545,13 @@ pub fn create_associated_token_account(
 /// * `mint` - Public key of the token mint
 /// * `token_account` - Public key of the destination token account
 /// * `amount` - Number of tokens to mint (in base units)
-/// * `mint_authority` - Keypair with mint authority
+/// * `mint_authority` - Keypair with mint authority, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the mint
 ///
 /// # Errors
 ///
-/// This function will return an error if the minting transaction fails.
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the minting transaction fails.
 ///
 /// # Example
 ///
@@ -193,8 +564,9 @@ pub fn create_associated_token_account(
 /// let mut litesvm = LiteSVM::new();
 /// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
 /// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
-/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None)?;
-/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token_2022::ID)?;
+/// let token_account =
+///     create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token_2022::ID)?;
 /// 
 /// mint_tokens_to_account(
 ///     &mut litesvm,
@@ -202,133 +574,1732 @@ pub fn create_associated_token_account(
 ///     &token_account,
 ///     1_000_000, // 1 token with 6 decimals
 ///     &mint_authority,
+///     &spl_token_2022::ID,
 /// )?;
 /// # Ok(())
 /// # }
 /// ```
-pub fn mint_tokens_to_account(
+pub fn mint_tokens_to_account<'a>(
     litesvm: &mut LiteSVM,
     mint: &Pubkey,
     token_account: &Pubkey,
     amount: u64,
-    mint_authority: &Keypair,
+    mint_authority: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
 ) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let mint_authority = mint_authority.into();
+    let fee_payer = mint_authority.fee_payer()?;
+
     let mint_to_instruction = mint_to(
-        &spl_token::id(),
+        token_program,
         mint,
         token_account,
         &mint_authority.pubkey(),
-        &[],
+        &mint_authority.signer_pubkeys(),
         amount,
     )
     .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create mint_to instruction: {:?}", e)))?;
 
-    let message = Message::new(&[mint_to_instruction], Some(&mint_authority.pubkey()));
-    let mut transaction = Transaction::new_unsigned(message);
-    let blockhash = litesvm.latest_blockhash();
-    transaction.sign(&[mint_authority], blockhash);
-    
-    litesvm
-        .send_transaction(transaction)
-        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to mint tokens: {:?}", e)))?;
+    send_transaction_from_instructions(
+        litesvm,
+        vec![mint_to_instruction],
+        &mint_authority.signers,
+        &fee_payer,
+    )?;
 
     Ok(())
 }
 
-/// Gets the token balance of a token account.
+/// Transfers tokens between two token accounts with an unchecked `Transfer` instruction.
 ///
-/// This function reads the token account data and extracts the balance from the
-/// SPL token account layout.
+/// Token-2022 rejects unchecked transfers for mints with a transfer fee, so use
+/// [`transfer_tokens_checked`] for those.
 ///
 /// # Arguments
 ///
-/// * `litesvm` - Reference to the LiteSVM instance
-/// * `token_account` - Public key of the token account to query
-///
-/// # Returns
-///
-/// Returns the token balance as a u64 in base units.
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `source` - Public key of the token account to transfer from
+/// * `destination` - Public key of the token account to transfer to
+/// * `amount` - Number of tokens to transfer (in base units)
+/// * `owner` - Keypair that owns the source account, or its delegate, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the accounts
 ///
 /// # Errors
 ///
-/// This function will return an error if the token account doesn't exist or
-/// the balance cannot be parsed.
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the transfer transaction fails.
 ///
 /// # Example
 ///
 /// ```rust
-/// use solana_kite::get_token_account_balance;
+/// use solana_kite::{
+///     create_associated_token_account, create_token_mint, create_wallet, mint_tokens_to_account,
+///     transfer_tokens,
+/// };
 /// use litesvm::LiteSVM;
-/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
 ///
-/// let litesvm = LiteSVM::new();
-/// let token_account = Pubkey::new_unique();
-/// 
-/// // This would fail in practice without a real token account
-/// // let balance = get_token_account_balance(&litesvm, &token_account)?;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let alice = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let bob = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let alice_account = create_associated_token_account(&mut litesvm, &alice.pubkey(), &mint, &alice, &spl_token::ID)?;
+/// let bob_account = create_associated_token_account(&mut litesvm, &bob.pubkey(), &mint, &bob, &spl_token::ID)?;
+/// mint_tokens_to_account(&mut litesvm, &mint, &alice_account, 1_000_000, &mint_authority, &spl_token::ID)?;
+///
+/// transfer_tokens(&mut litesvm, &alice_account, &bob_account, 250_000, &alice, &spl_token::ID)?;
+/// # Ok(())
+/// # }
 /// ```
-pub fn get_token_account_balance(
-    litesvm: &LiteSVM,
-    token_account: &Pubkey,
-) -> Result<u64, SolanaKiteError> {
-    let account = litesvm
-        .get_account(token_account)
-        .ok_or_else(|| SolanaKiteError::TokenOperationFailed("Token account not found".to_string()))?;
-    
-    let data = &account.data;
-    if data.len() < 72 {
-        return Err(SolanaKiteError::TokenOperationFailed(
-            "Invalid token account data length".to_string(),
-        ));
-    }
-    
-    // SPL Token account layout: amount is at bytes 64..72 (u64, little endian)
-    let amount_bytes = &data[64..72];
-    let amount = u64::from_le_bytes(
-        amount_bytes
-            .try_into()
-            .map_err(|_| SolanaKiteError::TokenOperationFailed("Failed to parse token amount".to_string()))?
-    );
-    
-    Ok(amount)
+pub fn transfer_tokens<'a>(
+    litesvm: &mut LiteSVM,
+    source: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+    owner: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let owner = owner.into();
+    let fee_payer = owner.fee_payer()?;
+
+    // Unchecked transfers are deprecated, but programs under test still use them
+    #[allow(deprecated)]
+    let transfer_instruction = spl_token_2022::instruction::transfer(
+        token_program,
+        source,
+        destination,
+        &owner.pubkey(),
+        &owner.signer_pubkeys(),
+        amount,
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create transfer instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![transfer_instruction],
+        &owner.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
 }
 
-/// Asserts that a token account has the expected balance.
+/// Transfers tokens between two token accounts with a `TransferChecked` instruction.
 ///
-/// This is a convenience function for testing that combines balance checking
-/// with assertion logic.
+/// The mint's decimals are read from the mint account. For Token-2022 mints with a
+/// transfer fee, the fee is withheld in the destination account, so the destination
+/// receives `amount` minus the fee.
 ///
 /// # Arguments
 ///
-/// * `litesvm` - Reference to the LiteSVM instance
-/// * `token_account` - Public key of the token account to check
-/// * `expected_balance` - Expected balance in base units
-/// * `message` - Custom error message for the assertion
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `mint` - Public key of the token mint
+/// * `source` - Public key of the token account to transfer from
+/// * `destination` - Public key of the token account to transfer to
+/// * `amount` - Number of tokens to transfer (in base units), including any fee
+/// * `owner` - Keypair that owns the source account, or its delegate, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the mint
 ///
-/// # Panics
+/// # Returns
 ///
-/// Panics if the actual balance doesn't match the expected balance.
+/// Returns the transfer fee withheld in the destination account, which is 0 for
+/// mints without a transfer fee.
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, the mint can't be read, or the transfer transaction fails.
 ///
 /// # Example
 ///
 /// ```rust
-/// use solana_kite::assert_token_balance;
+/// use solana_kite::{
+///     create_associated_token_account, create_wallet, get_token_account_balance, mint_tokens_to_account,
+///     transfer_tokens_checked, MintBuilder,
+/// };
 /// use litesvm::LiteSVM;
-/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
 ///
-/// let litesvm = LiteSVM::new();
-/// let token_account = Pubkey::new_unique();
-/// 
-/// // This would panic in practice without a real token account
-/// // assert_token_balance(&litesvm, &token_account, 1000000, "Balance should be 1 token");
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let alice = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let bob = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let token_program = spl_token_2022::ID;
+/// let mint = MintBuilder::new(&mint_authority, 6).transfer_fee(100, 1_000_000).create(&mut litesvm)?;
+/// let alice_account = create_associated_token_account(&mut litesvm, &alice.pubkey(), &mint, &alice, &token_program)?;
+/// let bob_account = create_associated_token_account(&mut litesvm, &bob.pubkey(), &mint, &bob, &token_program)?;
+/// mint_tokens_to_account(&mut litesvm, &mint, &alice_account, 1_000_000, &mint_authority, &token_program)?;
+///
+/// // A 1% fee is withheld from the transfer
+/// let withheld = transfer_tokens_checked(&mut litesvm, &mint, &alice_account, &bob_account, 100_000, &alice, &token_program)?;
+/// assert_eq!(withheld, 1_000);
+/// assert_eq!(get_token_account_balance(&litesvm, &bob_account, &token_program)?, 99_000);
+/// # Ok(())
+/// # }
 /// ```
-pub fn assert_token_balance(
-    litesvm: &LiteSVM,
-    token_account: &Pubkey,
-    expected_balance: u64,
-    message: &str,
-) {
-    let actual_balance = get_token_account_balance(litesvm, token_account)
-        .expect("Failed to get token account balance");
-    assert_eq!(actual_balance, expected_balance, "{}", message);
-}
\ No newline at end of file
+pub fn transfer_tokens_checked<'a>(
+    litesvm: &mut LiteSVM,
+    mint: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+    owner: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<u64, SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let owner = owner.into();
+    let fee_payer = owner.fee_payer()?;
+
+    let decimals = get_mint(litesvm, mint)?.decimals;
+    let withheld_before = get_token_account(litesvm, destination)?.withheld_amount();
+
+    let transfer_instruction = transfer_checked(
+        token_program,
+        source,
+        mint,
+        destination,
+        &owner.pubkey(),
+        &owner.signer_pubkeys(),
+        amount,
+        decimals,
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create transfer_checked instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![transfer_instruction],
+        &owner.signers,
+        &fee_payer,
+    )?;
+
+    Ok(get_token_account(litesvm, destination)?.withheld_amount() - withheld_before)
+}
+
+/// Burns tokens from a token account, reducing the mint's supply.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `mint` - Public key of the token mint
+/// * `token_account` - Public key of the token account to burn from
+/// * `amount` - Number of tokens to burn (in base units)
+/// * `owner` - Keypair that owns the token account, or its delegate, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the mint
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the burn transaction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{burn_tokens, create_associated_token_account, create_token_mint, create_wallet, mint_tokens_to_account};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+/// mint_tokens_to_account(&mut litesvm, &mint, &token_account, 1_000_000, &mint_authority, &spl_token::ID)?;
+///
+/// burn_tokens(&mut litesvm, &mint, &token_account, 400_000, &owner, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn burn_tokens<'a>(
+    litesvm: &mut LiteSVM,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    amount: u64,
+    owner: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let owner = owner.into();
+    let fee_payer = owner.fee_payer()?;
+
+    let burn_instruction = burn(
+        token_program,
+        token_account,
+        mint,
+        &owner.pubkey(),
+        &owner.signer_pubkeys(),
+        amount,
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create burn instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![burn_instruction],
+        &owner.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Approves a delegate to transfer or burn up to `amount` tokens from a token account.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `token_account` - Public key of the token account
+/// * `delegate` - Public key of the delegate
+/// * `amount` - Most tokens the delegate may move (in base units)
+/// * `owner` - Keypair that owns the token account, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the account
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the approve transaction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{approve_delegate, create_associated_token_account, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+/// let delegate = create_wallet(&mut litesvm, 1_000_000_000)?;
+///
+/// approve_delegate(&mut litesvm, &token_account, &delegate.pubkey(), 500_000, &owner, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn approve_delegate<'a>(
+    litesvm: &mut LiteSVM,
+    token_account: &Pubkey,
+    delegate: &Pubkey,
+    amount: u64,
+    owner: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let owner = owner.into();
+    let fee_payer = owner.fee_payer()?;
+
+    let approve_instruction = approve(
+        token_program,
+        token_account,
+        delegate,
+        &owner.pubkey(),
+        &owner.signer_pubkeys(),
+        amount,
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create approve instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![approve_instruction],
+        &owner.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Approves a delegate with an `ApproveChecked` instruction, which also checks the mint and its decimals.
+///
+/// The mint's decimals are read from the mint account.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `mint` - Public key of the token mint
+/// * `token_account` - Public key of the token account
+/// * `delegate` - Public key of the delegate
+/// * `amount` - Most tokens the delegate may move (in base units)
+/// * `owner` - Keypair that owns the token account, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the mint
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, the mint can't be read, or the approve transaction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{approve_delegate_checked, create_associated_token_account, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+/// let delegate = create_wallet(&mut litesvm, 1_000_000_000)?;
+///
+/// approve_delegate_checked(&mut litesvm, &mint, &token_account, &delegate.pubkey(), 500_000, &owner, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn approve_delegate_checked<'a>(
+    litesvm: &mut LiteSVM,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    delegate: &Pubkey,
+    amount: u64,
+    owner: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let owner = owner.into();
+    let fee_payer = owner.fee_payer()?;
+
+    let decimals = get_mint(litesvm, mint)?.decimals;
+    let approve_instruction = approve_checked(
+        token_program,
+        token_account,
+        mint,
+        delegate,
+        &owner.pubkey(),
+        &owner.signer_pubkeys(),
+        amount,
+        decimals,
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create approve_checked instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![approve_instruction],
+        &owner.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Revokes a token account's delegate.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `token_account` - Public key of the token account
+/// * `owner` - Keypair that owns the token account, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the account
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the revoke transaction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{approve_delegate, revoke_delegate, create_associated_token_account, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+/// let delegate = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// approve_delegate(&mut litesvm, &token_account, &delegate.pubkey(), 500_000, &owner, &spl_token::ID)?;
+///
+/// revoke_delegate(&mut litesvm, &token_account, &owner, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn revoke_delegate<'a>(
+    litesvm: &mut LiteSVM,
+    token_account: &Pubkey,
+    owner: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let owner = owner.into();
+    let fee_payer = owner.fee_payer()?;
+
+    let revoke_instruction = revoke(token_program, token_account, &owner.pubkey(), &owner.signer_pubkeys())
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create revoke instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![revoke_instruction],
+        &owner.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Freezes a token account, so its tokens can't be moved until it is thawed.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `mint` - Public key of the token mint
+/// * `token_account` - Public key of the token account to freeze
+/// * `freeze_authority` - Keypair with the mint's freeze authority, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the mint
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the freeze transaction fails, for example because
+/// the mint has no freeze authority.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{freeze_token_account, create_associated_token_account, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(
+///     &mut litesvm,
+///     &mint_authority,
+///     6,
+///     None,
+///     Some(mint_authority.pubkey()),
+///     &spl_token::ID,
+/// )?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+///
+/// freeze_token_account(&mut litesvm, &mint, &token_account, &mint_authority, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn freeze_token_account<'a>(
+    litesvm: &mut LiteSVM,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    freeze_authority: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let freeze_authority = freeze_authority.into();
+    let fee_payer = freeze_authority.fee_payer()?;
+
+    let freeze_instruction = freeze_account(
+        token_program,
+        token_account,
+        mint,
+        &freeze_authority.pubkey(),
+        &freeze_authority.signer_pubkeys(),
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create freeze_account instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![freeze_instruction],
+        &freeze_authority.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Thaws a frozen token account.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `mint` - Public key of the token mint
+/// * `token_account` - Public key of the token account to thaw
+/// * `freeze_authority` - Keypair with the mint's freeze authority, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the mint
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the thaw transaction fails, for example because the
+/// account isn't frozen.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{freeze_token_account, thaw_token_account, create_associated_token_account, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(
+///     &mut litesvm,
+///     &mint_authority,
+///     6,
+///     None,
+///     Some(mint_authority.pubkey()),
+///     &spl_token::ID,
+/// )?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+/// freeze_token_account(&mut litesvm, &mint, &token_account, &mint_authority, &spl_token::ID)?;
+///
+/// thaw_token_account(&mut litesvm, &mint, &token_account, &mint_authority, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn thaw_token_account<'a>(
+    litesvm: &mut LiteSVM,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    freeze_authority: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let freeze_authority = freeze_authority.into();
+    let fee_payer = freeze_authority.fee_payer()?;
+
+    let thaw_instruction = thaw_account(
+        token_program,
+        token_account,
+        mint,
+        &freeze_authority.pubkey(),
+        &freeze_authority.signer_pubkeys(),
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create thaw_account instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![thaw_instruction],
+        &freeze_authority.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Closes a token account, sending its rent lamports to `destination`.
+///
+/// The account must hold no tokens, and for Token-2022 no withheld transfer fees.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `token_account` - Public key of the token account to close
+/// * `destination` - Public key of the account that receives the rent lamports
+/// * `owner` - Keypair that owns the token account, or its close authority, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the account
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the close transaction fails, for example because the
+/// account still holds tokens.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{check_account_is_closed, close_token_account, create_associated_token_account, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+///
+/// close_token_account(&mut litesvm, &token_account, &owner.pubkey(), &owner, &spl_token::ID)?;
+/// check_account_is_closed(&litesvm, &token_account, "Token account should be closed");
+/// # Ok(())
+/// # }
+/// ```
+pub fn close_token_account<'a>(
+    litesvm: &mut LiteSVM,
+    token_account: &Pubkey,
+    destination: &Pubkey,
+    owner: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let owner = owner.into();
+    let fee_payer = owner.fee_payer()?;
+
+    let close_instruction = close_account(
+        token_program,
+        token_account,
+        destination,
+        &owner.pubkey(),
+        &owner.signer_pubkeys(),
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create close_account instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![close_instruction],
+        &owner.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Changes or removes an authority of a mint or token account.
+///
+/// Works for every [`AuthorityType`], including the Token-2022 extension authorities.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `account` - Public key of the mint or token account
+/// * `authority_type` - Which authority to change
+/// * `new_authority` - The new authority, or None to remove the authority for good
+/// * `current_authority` - Keypair with the current authority, or a multisig [`TokenAuthority`]
+/// * `token_program` - The token program that owns the account
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the set authority transaction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{set_token_authority, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use spl_token_2022::instruction::AuthorityType;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+///
+/// // Fix the supply by removing the mint authority
+/// set_token_authority(&mut litesvm, &mint, AuthorityType::MintTokens, None, &mint_authority, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_token_authority<'a>(
+    litesvm: &mut LiteSVM,
+    account: &Pubkey,
+    authority_type: AuthorityType,
+    new_authority: Option<&Pubkey>,
+    current_authority: impl Into<TokenAuthority<'a>>,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+    let current_authority = current_authority.into();
+    let fee_payer = current_authority.fee_payer()?;
+
+    let set_authority_instruction = set_authority(
+        token_program,
+        account,
+        new_authority,
+        authority_type,
+        &current_authority.pubkey(),
+        &current_authority.signer_pubkeys(),
+    )
+    .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create set_authority instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![set_authority_instruction],
+        &current_authority.signers,
+        &fee_payer,
+    )?;
+
+    Ok(())
+}
+
+/// Wraps native SOL into wrapped SOL tokens.
+///
+/// Creates the owner's wrapped SOL associated token account if it doesn't exist,
+/// transfers `lamports` into it and syncs its token balance, all in one transaction.
+/// The native mint account is created first if LiteSVM doesn't have it yet.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `owner` - Keypair that owns the SOL to wrap, and pays for the transaction
+/// * `lamports` - Lamports to wrap
+/// * `token_program` - The token program whose native mint to use
+///
+/// # Returns
+///
+/// Returns the public key of the owner's wrapped SOL token account.
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or if the wrap transaction fails, for example because the
+/// owner doesn't have enough SOL.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{assert_token_balance, create_wallet, wrap_sol};
+/// use litesvm::LiteSVM;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let owner = create_wallet(&mut litesvm, 2_000_000_000)?;
+///
+/// let wsol_account = wrap_sol(&mut litesvm, &owner, 1_000_000_000, &spl_token::ID)?;
+/// assert_token_balance(&litesvm, &wsol_account, 1_000_000_000, "Should hold 1 wrapped SOL");
+/// # Ok(())
+/// # }
+/// ```
+pub fn wrap_sol(
+    litesvm: &mut LiteSVM,
+    owner: &Keypair,
+    lamports: u64,
+    token_program: &Pubkey,
+) -> Result<Pubkey, SolanaKiteError> {
+    let native_mint = ensure_native_mint(litesvm, token_program)?;
+    let wsol_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(&owner.pubkey(), &native_mint, token_program);
+
+    let create_ata_instruction = create_ata_idempotent_instruction(
+        &owner.pubkey(),
+        &owner.pubkey(),
+        &native_mint,
+        token_program,
+    );
+    let transfer_instruction =
+        solana_program::system_instruction::transfer(&owner.pubkey(), &wsol_account, lamports);
+    let sync_native_instruction = sync_native(token_program, &wsol_account)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create sync_native instruction: {:?}", e)))?;
+
+    send_transaction_from_instructions(
+        litesvm,
+        vec![create_ata_instruction, transfer_instruction, sync_native_instruction],
+        &[owner],
+        &owner.pubkey(),
+    )?;
+
+    Ok(wsol_account)
+}
+
+/// Unwraps all of an owner's wrapped SOL by closing their wrapped SOL associated token account.
+///
+/// The account's lamports, both the wrapped SOL and its rent, go back to the owner.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `owner` - Keypair that owns the wrapped SOL, and pays for the transaction
+/// * `token_program` - The token program whose native mint was used
+///
+/// # Returns
+///
+/// Returns the amount of wrapped SOL unwrapped, in lamports.
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, the owner has no wrapped SOL account, or the close
+/// transaction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_wallet, unwrap_sol, wrap_sol};
+/// use litesvm::LiteSVM;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let owner = create_wallet(&mut litesvm, 2_000_000_000)?;
+/// wrap_sol(&mut litesvm, &owner, 1_000_000_000, &spl_token::ID)?;
+///
+/// let unwrapped = unwrap_sol(&mut litesvm, &owner, &spl_token::ID)?;
+/// assert_eq!(unwrapped, 1_000_000_000);
+/// # Ok(())
+/// # }
+/// ```
+pub fn unwrap_sol(
+    litesvm: &mut LiteSVM,
+    owner: &Keypair,
+    token_program: &Pubkey,
+) -> Result<u64, SolanaKiteError> {
+    let native_mint = native_mint_of(token_program)?;
+    let wsol_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(&owner.pubkey(), &native_mint, token_program);
+    let amount = get_token_account_balance(litesvm, &wsol_account, token_program)?;
+
+    close_token_account(litesvm, &wsol_account, &owner.pubkey(), owner, token_program)?;
+
+    Ok(amount)
+}
+
+/// Gets the token balance of a token account.
+///
+/// This function unpacks the token account with [`get_token_account`] and returns
+/// its balance, after checking the account belongs to `token_program`.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `token_account` - Public key of the token account to query
+/// * `token_program` - The token program expected to own the account
+///
+/// # Returns
+///
+/// Returns the token balance as a u64 in base units.
+///
+/// # Errors
+///
+/// This function will return an error if the token account doesn't exist, isn't
+/// owned by `token_program`, or the balance cannot be parsed.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::get_token_account_balance;
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+///
+/// let litesvm = LiteSVM::new();
+/// let token_account = Pubkey::new_unique();
+/// 
+/// // This would fail in practice without a real token account
+/// // let balance = get_token_account_balance(&litesvm, &token_account, &spl_token::ID)?;
+/// ```
+pub fn get_token_account_balance(
+    litesvm: &LiteSVM,
+    token_account: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<u64, SolanaKiteError> {
+    let token_account_state = get_token_account(litesvm, token_account)?;
+    if token_account_state.token_program != *token_program {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "Token account {} is owned by {}, not {}",
+            token_account, token_account_state.token_program, token_program
+        )));
+    }
+
+    Ok(token_account_state.amount)
+}
+
+/// A mint, unpacked from its account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenMint {
+    /// The token program that owns the mint.
+    pub token_program: Pubkey,
+    /// The authority that can mint new tokens, if any.
+    pub mint_authority: Option<Pubkey>,
+    /// Total supply of tokens, in base units.
+    pub supply: u64,
+    /// Number of decimal places.
+    pub decimals: u8,
+    /// The authority that can freeze token accounts, if any.
+    pub freeze_authority: Option<Pubkey>,
+    /// The authority that can close the mint, from the Token-2022 mint close authority extension.
+    pub close_authority: Option<Pubkey>,
+    /// The Token-2022 extensions the mint has. Always empty for SPL Token mints.
+    pub extension_types: Vec<ExtensionType>,
+    data: Vec<u8>,
+}
+
+impl TokenMint {
+    /// Returns the mint's base state, for example to modify and pass to [`set_mint_state`].
+    pub fn to_mint_state(&self) -> Mint {
+        Mint {
+            mint_authority: self.mint_authority.into(),
+            supply: self.supply,
+            decimals: self.decimals,
+            is_initialized: true,
+            freeze_authority: self.freeze_authority.into(),
+        }
+    }
+
+    /// Returns a copy of a fixed-size Token-2022 extension, such as
+    /// `TransferFeeConfig`, or None if the mint doesn't have it.
+    pub fn extension<V: Extension + Pod>(&self) -> Option<V> {
+        get_extension::<Mint, V>(&self.data)
+    }
+
+    /// Returns the token metadata stored in the mint, if any.
+    pub fn token_metadata(&self) -> Option<TokenMetadata> {
+        StateWithExtensions::<Mint>::unpack(&self.data)
+            .ok()?
+            .get_variable_len_extension::<TokenMetadata>()
+            .ok()
+    }
+}
+
+/// A token account, unpacked from its account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenAccount {
+    /// The token program that owns the account.
+    pub token_program: Pubkey,
+    /// The mint of the tokens held.
+    pub mint: Pubkey,
+    /// The owner of the tokens.
+    pub owner: Pubkey,
+    /// Tokens held, in base units.
+    pub amount: u64,
+    /// The delegate allowed to move some of the tokens, if any.
+    pub delegate: Option<Pubkey>,
+    /// Tokens the delegate may still move, in base units.
+    pub delegated_amount: u64,
+    /// Whether the account is initialized or frozen.
+    pub state: AccountState,
+    /// For wrapped SOL accounts, the lamports reserved for rent exemption.
+    pub is_native: Option<u64>,
+    /// The authority that can close the account, if it isn't the owner.
+    pub close_authority: Option<Pubkey>,
+    /// The Token-2022 extensions the account has. Always empty for SPL Token accounts.
+    pub extension_types: Vec<ExtensionType>,
+    data: Vec<u8>,
+}
+
+impl TokenAccount {
+    /// Returns true if the account is frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.state == AccountState::Frozen
+    }
+
+    /// Returns the Token-2022 transfer fees withheld in the account, or 0 without the transfer fee extension.
+    pub fn withheld_amount(&self) -> u64 {
+        self.extension::<TransferFeeAmount>()
+            .map(|transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount))
+            .unwrap_or(0)
+    }
+
+    /// Returns a copy of a fixed-size Token-2022 extension, such as
+    /// `TransferFeeAmount`, or None if the account doesn't have it.
+    pub fn extension<V: Extension + Pod>(&self) -> Option<V> {
+        get_extension::<Account, V>(&self.data)
+    }
+}
+
+/// Reads and unpacks a mint of either token program.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `mint` - Public key of the mint
+///
+/// # Returns
+///
+/// Returns the mint's supply, decimals, authorities and Token-2022 extensions.
+///
+/// # Errors
+///
+/// This function will return an error if the account doesn't exist, isn't owned
+/// by a token program, or isn't a mint.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_wallet, get_mint, MintBuilder};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+/// use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = MintBuilder::new(&mint_authority, 6).transfer_fee(100, 1_000).create(&mut litesvm)?;
+///
+/// let mint_state = get_mint(&litesvm, &mint)?;
+/// assert_eq!(mint_state.decimals, 6);
+/// assert_eq!(mint_state.mint_authority, Some(mint_authority.pubkey()));
+/// let transfer_fee_config = mint_state.extension::<TransferFeeConfig>().unwrap();
+/// # Ok(())
+/// # }
+/// ```
+pub fn get_mint(litesvm: &LiteSVM, mint: &Pubkey) -> Result<TokenMint, SolanaKiteError> {
+    let account = get_token_program_account(litesvm, mint)?;
+    let state = StateWithExtensions::<Mint>::unpack(&account.data)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to parse mint {}: {:?}", mint, e)))?;
+    let extension_types = state
+        .get_extension_types()
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to parse extensions of mint {}: {:?}", mint, e)))?;
+    let close_authority = state
+        .get_extension::<MintCloseAuthority>()
+        .ok()
+        .and_then(|extension| Option::<Pubkey>::from(extension.close_authority));
+
+    Ok(TokenMint {
+        token_program: account.owner,
+        mint_authority: state.base.mint_authority.into(),
+        supply: state.base.supply,
+        decimals: state.base.decimals,
+        freeze_authority: state.base.freeze_authority.into(),
+        close_authority,
+        extension_types,
+        data: account.data.clone(),
+    })
+}
+
+/// Reads and unpacks a token account of either token program.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `token_account` - Public key of the token account
+///
+/// # Returns
+///
+/// Returns the account's mint, owner, balance, delegation, state and Token-2022 extensions.
+///
+/// # Errors
+///
+/// This function will return an error if the account doesn't exist, isn't owned
+/// by a token program, or isn't a token account.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_associated_token_account, create_token_mint, create_wallet, get_token_account};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let owner = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID)?;
+///
+/// let token_account_state = get_token_account(&litesvm, &token_account)?;
+/// assert_eq!(token_account_state.owner, owner.pubkey());
+/// assert_eq!(token_account_state.amount, 0);
+/// assert!(!token_account_state.is_frozen());
+/// # Ok(())
+/// # }
+/// ```
+pub fn get_token_account(litesvm: &LiteSVM, token_account: &Pubkey) -> Result<TokenAccount, SolanaKiteError> {
+    let account = get_token_program_account(litesvm, token_account)?;
+    let state = StateWithExtensions::<Account>::unpack(&account.data).map_err(|e| {
+        SolanaKiteError::TokenOperationFailed(format!("Failed to parse token account {}: {:?}", token_account, e))
+    })?;
+    let extension_types = state.get_extension_types().map_err(|e| {
+        SolanaKiteError::TokenOperationFailed(format!("Failed to parse extensions of token account {}: {:?}", token_account, e))
+    })?;
+
+    Ok(TokenAccount {
+        token_program: account.owner,
+        mint: state.base.mint,
+        owner: state.base.owner,
+        amount: state.base.amount,
+        delegate: state.base.delegate.into(),
+        delegated_amount: state.base.delegated_amount,
+        state: state.base.state,
+        is_native: state.base.is_native.into(),
+        close_authority: state.base.close_authority.into(),
+        extension_types,
+        data: account.data.clone(),
+    })
+}
+
+/// Sets a wallet's token balance directly, without sending a transaction.
+///
+/// Writes the owner's associated token account straight into LiteSVM, creating it
+/// if it doesn't exist, and adjusts the mint's supply by the change in balance, so
+/// no mint authority keypair is needed. The account gets the extensions Token-2022
+/// would give it for the mint's extensions, and starts frozen if the mint's default
+/// account state is frozen. For the wrapped SOL mint, the account also holds the
+/// balance as lamports, and the native mint is created if LiteSVM doesn't have it.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `owner` - Public key of the wallet that owns the tokens
+/// * `mint` - Public key of the token mint, which must already exist unless it's a native mint
+/// * `amount` - The new balance (in base units)
+///
+/// # Returns
+///
+/// Returns the public key of the owner's associated token account.
+///
+/// # Errors
+///
+/// This function will return an error if the mint can't be read, the mint has
+/// an extension whose account state this function can't fill in, an account not
+/// owned by the mint's token program is at the associated token account address,
+/// or the mint's supply is less than the account's current balance.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{assert_token_balance, set_mint_state, set_token_balance};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use spl_token_2022::state::Mint;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let usdc = Pubkey::new_unique();
+/// let user = Pubkey::new_unique();
+///
+/// // A USDC-like mint with no mint authority keypair in sight
+/// let mint_state = Mint { decimals: 6, is_initialized: true, ..Default::default() };
+/// set_mint_state(&mut litesvm, &usdc, &mint_state, &spl_token::ID)?;
+///
+/// // Give the user 1M USDC
+/// let token_account = set_token_balance(&mut litesvm, &user, &usdc, 1_000_000_000_000)?;
+/// assert_token_balance(&litesvm, &token_account, 1_000_000_000_000, "User should have 1M USDC");
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_token_balance(
+    litesvm: &mut LiteSVM,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Result<Pubkey, SolanaKiteError> {
+    let is_native = *mint == spl_token::native_mint::ID || *mint == spl_token_2022::native_mint::ID;
+    if *mint == spl_token::native_mint::ID {
+        ensure_native_mint(litesvm, &spl_token::ID)?;
+    } else if *mint == spl_token_2022::native_mint::ID {
+        ensure_native_mint(litesvm, &spl_token_2022::ID)?;
+    }
+
+    let mint_state = get_mint(litesvm, mint)?;
+    let token_program = mint_state.token_program;
+    let token_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(owner, mint, &token_program);
+
+    let (mut account, previous_amount) = match litesvm.get_account(&token_account) {
+        Some(account) if account.owner == token_program => {
+            let previous_amount = get_token_account(litesvm, &token_account)?.amount;
+            (account, previous_amount)
+        }
+        Some(account) => {
+            return Err(SolanaKiteError::TokenOperationFailed(format!(
+                "Account {} is owned by {}, not {}",
+                token_account, account.owner, token_program
+            )));
+        }
+        None => (new_associated_token_account(litesvm, owner, mint, &mint_state, is_native)?, 0),
+    };
+
+    // Wrapped SOL balances are backed by lamports, and the native mint has no supply
+    let supply = if is_native {
+        None
+    } else {
+        let supply = mint_state
+            .supply
+            .checked_sub(previous_amount)
+            .ok_or_else(|| {
+                SolanaKiteError::TokenOperationFailed(format!(
+                    "Supply of mint {} is {}, less than the {} held by {}",
+                    mint, mint_state.supply, previous_amount, token_account
+                ))
+            })?
+            .checked_add(amount)
+            .ok_or_else(|| SolanaKiteError::TokenOperationFailed(format!("Supply of mint {} would overflow", mint)))?;
+        Some(supply)
+    };
+
+    let mut state = StateWithExtensionsMut::<Account>::unpack(&mut account.data).map_err(|e| {
+        SolanaKiteError::TokenOperationFailed(format!("Failed to parse token account {}: {:?}", token_account, e))
+    })?;
+    state.base.amount = amount;
+    state.pack_base();
+
+    if is_native {
+        let rent_exempt_reserve = litesvm.minimum_balance_for_rent_exemption(account.data.len());
+        account.lamports = rent_exempt_reserve + amount;
+    }
+
+    litesvm
+        .set_account(token_account, account)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to set token account: {:?}", e)))?;
+
+    if let Some(supply) = supply {
+        update_mint(litesvm, mint, |mint| mint.supply = supply)?;
+    }
+
+    Ok(token_account)
+}
+
+/// Sets a mint's supply, decimals and authorities directly, without sending a transaction.
+///
+/// If the mint already exists, its base state is overwritten and any Token-2022
+/// extensions are kept. Otherwise a mint without extensions is created at that
+/// address, which is handy for recreating a mainnet mint such as USDC.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `mint` - Public key of the mint
+/// * `mint_state` - The mint's new state. Set `is_initialized` to true
+/// * `token_program` - The token program that owns the mint
+///
+/// # Errors
+///
+/// This function will return an error if `token_program` isn't a token program
+/// loaded in LiteSVM, or the mint exists but belongs to a different program.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_token_mint, create_wallet, get_mint, set_mint_state};
+/// use litesvm::LiteSVM;
+/// use solana_program::program_option::COption;
+/// use solana_pubkey::Pubkey;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+///
+/// // Hand the mint authority to a PDA
+/// let mut mint_state = get_mint(&litesvm, &mint)?.to_mint_state();
+/// mint_state.mint_authority = COption::Some(Pubkey::new_unique());
+/// set_mint_state(&mut litesvm, &mint, &mint_state, &spl_token::ID)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_mint_state(
+    litesvm: &mut LiteSVM,
+    mint: &Pubkey,
+    mint_state: &Mint,
+    token_program: &Pubkey,
+) -> Result<(), SolanaKiteError> {
+    check_token_program(litesvm, token_program)?;
+
+    match litesvm.get_account(mint) {
+        Some(account) if account.owner == *token_program => update_mint(litesvm, mint, |mint| *mint = *mint_state),
+        Some(account) => Err(SolanaKiteError::TokenOperationFailed(format!(
+            "Mint {} is owned by {}, not {}",
+            mint, account.owner, token_program
+        ))),
+        None => {
+            let mut data = vec![0u8; Mint::LEN];
+            Mint::pack(*mint_state, &mut data)
+                .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to pack mint: {:?}", e)))?;
+            litesvm
+                .set_account(
+                    *mint,
+                    solana_account::Account {
+                        lamports: litesvm.minimum_balance_for_rent_exemption(data.len()),
+                        data,
+                        owner: *token_program,
+                        executable: false,
+                        rent_epoch: 0,
+                    },
+                )
+                .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create mint account: {:?}", e)))
+        }
+    }
+}
+
+/// Asserts that a token account has the expected balance.
+///
+/// This is a convenience function for testing that combines balance checking
+/// with assertion logic. It works for token accounts of either token program.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `token_account` - Public key of the token account to check
+/// * `expected_balance` - Expected balance in base units
+/// * `message` - Custom error message for the assertion
+///
+/// # Panics
+///
+/// Panics if the actual balance doesn't match the expected balance.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::assert_token_balance;
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+///
+/// let litesvm = LiteSVM::new();
+/// let token_account = Pubkey::new_unique();
+/// 
+/// // This would panic in practice without a real token account
+/// // assert_token_balance(&litesvm, &token_account, 1000000, "Balance should be 1 token");
+/// ```
+pub fn assert_token_balance(
+    litesvm: &LiteSVM,
+    token_account: &Pubkey,
+    expected_balance: u64,
+    message: &str,
+) {
+    let actual_balance = get_token_account(litesvm, token_account)
+        .expect("Failed to get token account balance")
+        .amount;
+    assert_eq!(actual_balance, expected_balance, "{}", message);
+}
+
+/// Converts an amount in base units to a human-readable amount, using the mint's decimals.
+///
+/// The conversion is exact: it shifts the decimal point rather than dividing as a
+/// float, and drops trailing zeros. Interest-bearing and scaled UI amount mints are
+/// converted by their decimals only.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `mint` - Public key of the token mint
+/// * `amount` - Amount in base units
+///
+/// # Returns
+///
+/// Returns the amount as a decimal string, such as `"12.5"` for 12,500,000 base units of a 6 decimal mint.
+///
+/// # Errors
+///
+/// Returns an error if the mint can't be read.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_token_mint, create_wallet, to_ui_amount};
+/// use litesvm::LiteSVM;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+///
+/// assert_eq!(to_ui_amount(&litesvm, &mint, 12_500_000)?, "12.5");
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_ui_amount(litesvm: &LiteSVM, mint: &Pubkey, amount: u64) -> Result<String, SolanaKiteError> {
+    let decimals = get_mint(litesvm, mint)?.decimals;
+    Ok(format_ui_amount(amount, decimals))
+}
+
+/// Converts a human-readable amount to base units, using the mint's decimals.
+///
+/// The amount is parsed as a decimal string, so no precision is lost to float
+/// rounding. Trailing zeros beyond the mint's decimals are allowed.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `mint` - Public key of the token mint
+/// * `ui_amount` - Amount as a decimal string, such as `"12.5"`
+///
+/// # Returns
+///
+/// Returns the amount in base units.
+///
+/// # Errors
+///
+/// Returns an error if the mint can't be read, if `ui_amount` isn't a plain decimal
+/// number with digits on both sides of any decimal point, such as `"0.5"` rather than
+/// `".5"`, if it has more decimal places than the mint, or if it doesn't fit in a `u64`.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_token_mint, create_wallet, from_ui_amount};
+/// use litesvm::LiteSVM;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+///
+/// assert_eq!(from_ui_amount(&litesvm, &mint, "12.5")?, 12_500_000);
+/// assert!(from_ui_amount(&litesvm, &mint, "0.0000001").is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_ui_amount(litesvm: &LiteSVM, mint: &Pubkey, ui_amount: &str) -> Result<u64, SolanaKiteError> {
+    let decimals = get_mint(litesvm, mint)?.decimals;
+    parse_ui_amount(ui_amount, decimals)
+}
+
+/// Asserts that a token account holds the expected human-readable amount.
+///
+/// Like [`assert_token_balance`], but the expected balance is written the way a
+/// person would, such as `"12.5"`, and converted using the mint's decimals.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `token_account` - Public key of the token account to check
+/// * `expected_ui_balance` - Expected balance as a decimal string
+///
+/// # Panics
+///
+/// Panics if the token account or its mint can't be read, if `expected_ui_balance`
+/// isn't a valid amount for the mint, or if the balance doesn't match.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{assert_token_ui_balance, create_token_mint, create_wallet, set_token_balance};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = set_token_balance(&mut litesvm, &mint_authority.pubkey(), &mint, 12_500_000)?;
+///
+/// assert_token_ui_balance(&litesvm, &token_account, "12.5");
+/// # Ok(())
+/// # }
+/// ```
+pub fn assert_token_ui_balance(litesvm: &LiteSVM, token_account: &Pubkey, expected_ui_balance: &str) {
+    let account = get_token_account(litesvm, token_account).expect("Failed to get token account");
+    let decimals = get_mint(litesvm, &account.mint).expect("Failed to get mint").decimals;
+    let expected_balance = parse_ui_amount(expected_ui_balance, decimals).expect("Invalid expected balance");
+    assert_eq!(
+        account.amount,
+        expected_balance,
+        "Token account {} should hold {} tokens, but holds {}",
+        token_account,
+        expected_ui_balance,
+        format_ui_amount(account.amount, decimals)
+    );
+}
+
+/// Checks that `token_program` is SPL Token or Token-2022, and that LiteSVM has it loaded.
+pub(crate) fn check_token_program(litesvm: &LiteSVM, token_program: &Pubkey) -> Result<(), SolanaKiteError> {
+    if *token_program != spl_token::ID && *token_program != spl_token_2022::ID {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "{} is not a token program",
+            token_program
+        )));
+    }
+    if litesvm.get_account(token_program).is_none() {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "Token program {} is not loaded in LiteSVM",
+            token_program
+        )));
+    }
+    Ok(())
+}
+
+/// Gets an account, checking that a token program owns it.
+fn get_token_program_account(litesvm: &LiteSVM, address: &Pubkey) -> Result<solana_account::Account, SolanaKiteError> {
+    let account = litesvm
+        .get_account(address)
+        .ok_or_else(|| SolanaKiteError::TokenOperationFailed(format!("Account {} not found", address)))?;
+    if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "Account {} is owned by {}, not a token program",
+            address, account.owner
+        )));
+    }
+    Ok(account)
+}
+
+fn get_extension<S: BaseState + Pack, V: Extension + Pod>(data: &[u8]) -> Option<V> {
+    StateWithExtensions::<S>::unpack(data)
+        .ok()?
+        .get_extension::<V>()
+        .ok()
+        .copied()
+}
+
+/// Builds an empty associated token account for `owner`, with the extensions Token-2022 requires for the mint.
+fn new_associated_token_account(
+    litesvm: &LiteSVM,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    mint_state: &TokenMint,
+    is_native: bool,
+) -> Result<solana_account::Account, SolanaKiteError> {
+    let mut extension_types = Vec::new();
+    if mint_state.token_program == spl_token_2022::ID {
+        // The associated token account program makes every Token-2022 account's owner immutable
+        extension_types = ExtensionType::get_required_init_account_extensions(&mint_state.extension_types);
+        if !extension_types.contains(&ExtensionType::ImmutableOwner) {
+            extension_types.push(ExtensionType::ImmutableOwner);
+        }
+    }
+
+    let space = ExtensionType::try_calculate_account_len::<Account>(&extension_types)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to calculate token account size: {:?}", e)))?;
+    let rent_exempt_reserve = litesvm.minimum_balance_for_rent_exemption(space);
+    let mut data = vec![0u8; space];
+
+    let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create token account: {:?}", e)))?;
+    for extension_type in &extension_types {
+        let initialized = match extension_type {
+            ExtensionType::ImmutableOwner => state.init_extension::<ImmutableOwner>(true).map(|_| ()),
+            ExtensionType::TransferFeeAmount => state.init_extension::<TransferFeeAmount>(true).map(|_| ()),
+            ExtensionType::NonTransferableAccount => state.init_extension::<NonTransferableAccount>(true).map(|_| ()),
+            ExtensionType::TransferHookAccount => state.init_extension::<TransferHookAccount>(true).map(|_| ()),
+            ExtensionType::PausableAccount => state.init_extension::<PausableAccount>(true).map(|_| ()),
+            unsupported => {
+                return Err(SolanaKiteError::TokenOperationFailed(format!(
+                    "Can't set balances for mints that need the {:?} account extension",
+                    unsupported
+                )))
+            }
+        };
+        initialized.map_err(|e| {
+            SolanaKiteError::TokenOperationFailed(format!("Failed to initialize {:?} extension: {:?}", extension_type, e))
+        })?;
+    }
+
+    let default_state = mint_state
+        .extension::<DefaultAccountState>()
+        .and_then(|default_state| AccountState::try_from(default_state.state).ok())
+        .unwrap_or(AccountState::Initialized);
+    state.base = Account {
+        mint: *mint,
+        owner: *owner,
+        state: default_state,
+        is_native: if is_native { COption::Some(rent_exempt_reserve) } else { COption::None },
+        ..Default::default()
+    };
+    state.pack_base();
+    if !extension_types.is_empty() {
+        state
+            .init_account_type()
+            .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to create token account: {:?}", e)))?;
+    }
+
+    Ok(solana_account::Account {
+        lamports: rent_exempt_reserve,
+        data,
+        owner: mint_state.token_program,
+        executable: false,
+        rent_epoch: 0,
+    })
+}
+
+/// Modifies a mint's base state in place, keeping its extensions.
+fn update_mint(litesvm: &mut LiteSVM, mint: &Pubkey, update: impl FnOnce(&mut Mint)) -> Result<(), SolanaKiteError> {
+    let mut account = litesvm
+        .get_account(mint)
+        .ok_or_else(|| SolanaKiteError::TokenOperationFailed(format!("Mint {} not found", mint)))?;
+    let mut state = StateWithExtensionsMut::<Mint>::unpack(&mut account.data)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to parse mint {}: {:?}", mint, e)))?;
+    update(&mut state.base);
+    state.pack_base();
+
+    litesvm
+        .set_account(*mint, account)
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Failed to set mint account: {:?}", e)))
+}
+
+/// Returns the wrapped SOL mint of a token program.
+fn native_mint_of(token_program: &Pubkey) -> Result<Pubkey, SolanaKiteError> {
+    if *token_program == spl_token::ID {
+        Ok(spl_token::native_mint::ID)
+    } else if *token_program == spl_token_2022::ID {
+        Ok(spl_token_2022::native_mint::ID)
+    } else {
+        Err(SolanaKiteError::TokenOperationFailed(format!(
+            "{} is not a token program",
+            token_program
+        )))
+    }
+}
+
+/// Creates a token program's native mint if LiteSVM doesn't have it, as it exists on every cluster.
+fn ensure_native_mint(litesvm: &mut LiteSVM, token_program: &Pubkey) -> Result<Pubkey, SolanaKiteError> {
+    let native_mint = native_mint_of(token_program)?;
+    if litesvm.get_account(&native_mint).is_none() {
+        let mint_state = Mint {
+            decimals: spl_token::native_mint::DECIMALS,
+            is_initialized: true,
+            ..Default::default()
+        };
+        set_mint_state(litesvm, &native_mint, &mint_state, token_program)?;
+    }
+    Ok(native_mint)
+}
+
+/// Formats base units as a decimal string with `decimals` places, without trailing zeros.
+fn format_ui_amount(amount: u64, decimals: u8) -> String {
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", amount, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Parses a decimal string into base units with `decimals` places.
+fn parse_ui_amount(ui_amount: &str, decimals: u8) -> Result<u64, SolanaKiteError> {
+    let decimals = decimals as usize;
+    // Both the whole part and any fractional part need digits, so ".5", "1." and "." are rejected
+    let (whole, fraction) = match ui_amount.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (ui_amount, None),
+    };
+    let is_digits = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if !is_digits(whole) || !fraction.is_none_or(is_digits) {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "'{}' is not a valid amount",
+            ui_amount
+        )));
+    }
+    let fraction = fraction.unwrap_or_default();
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals {
+        return Err(SolanaKiteError::TokenOperationFailed(format!(
+            "Token amount '{}' has more than {} decimal places",
+            ui_amount, decimals
+        )));
+    }
+
+    format!("{}{:0<width$}", whole, fraction, width = decimals)
+        .parse()
+        .map_err(|e| SolanaKiteError::TokenOperationFailed(format!("Token amount '{}' is too large: {}", ui_amount, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_ui_amount() {
+        assert_eq!(format_ui_amount(12_500_000, 6), "12.5");
+        assert_eq!(format_ui_amount(1_000_000, 6), "1");
+        assert_eq!(format_ui_amount(1, 6), "0.000001");
+        assert_eq!(format_ui_amount(0, 9), "0");
+        assert_eq!(format_ui_amount(42, 0), "42");
+        assert_eq!(format_ui_amount(u64::MAX, 9), "18446744073.709551615");
+        assert_eq!(format_ui_amount(5, 30), "0.000000000000000000000000000005");
+    }
+
+    #[test]
+    fn test_parse_ui_amount() {
+        assert_eq!(parse_ui_amount("12.5", 6).unwrap(), 12_500_000);
+        assert_eq!(parse_ui_amount("1", 6).unwrap(), 1_000_000);
+        assert_eq!(parse_ui_amount("0.5", 1).unwrap(), 5);
+        assert_eq!(parse_ui_amount("3", 2).unwrap(), 300);
+        assert_eq!(parse_ui_amount("1.500", 1).unwrap(), 15);
+        assert_eq!(parse_ui_amount("0.000001", 6).unwrap(), 1);
+        assert_eq!(parse_ui_amount("18446744073.709551615", 9).unwrap(), u64::MAX);
+
+        assert!(parse_ui_amount("0.0000001", 6).is_err());
+        assert!(parse_ui_amount("18446744073.709551616", 9).is_err());
+        for invalid in ["", ".", ".0", ".5", "1.", "-1", "+1", "1.2.3", "1,000", " 1", "1e6", "abc"] {
+            assert!(
+                matches!(
+                    parse_ui_amount(invalid, 6),
+                    Err(SolanaKiteError::TokenOperationFailed(message)) if message.contains("not a valid amount")
+                ),
+                "'{}' should be rejected",
+                invalid
+            );
+        }
+    }
+}
@@ -1,11 +1,157 @@
 //! Transaction utilities for sending Solana transactions.
 
-use crate::error::SolanaKiteError;
+use crate::error::{SolanaKiteError, TransactionFailure};
+use crate::logs::{format_call_trace, parse_program_logs, ProgramInvocation};
+use crate::lookup_table::load_lookup_table_accounts;
+use agave_reserved_account_keys::ReservedAccountKeys;
+use borsh::BorshDeserialize;
+use litesvm::types::TransactionMetadata;
 use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_fee::{calculate_fee, FeeFeatures};
+use solana_fee_structure::FeeStructure;
+use solana_instruction::error::InstructionError;
 use solana_keypair::Keypair;
-use solana_message::Message;
+use solana_message::inner_instruction::InnerInstructionsList;
+use solana_message::{v0, Message, VersionedMessage};
 use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_transaction::sanitized::{MessageHash, SanitizedTransaction};
+use solana_transaction::versioned::VersionedTransaction;
 use solana_transaction::Transaction;
+use solana_transaction_context::TransactionReturnData;
+
+/// The outcome of a successfully executed transaction.
+///
+/// This keeps everything LiteSVM reports about an executed transaction, so tests can
+/// assert on what actually happened rather than only on whether it succeeded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionResult {
+    /// The transaction signature.
+    pub signature: Signature,
+    /// Program logs emitted while executing the transaction.
+    pub logs: Vec<String>,
+    /// Total compute units consumed by the transaction.
+    pub compute_units_consumed: u64,
+    /// Fee charged to the fee payer, in lamports, including precompile signatures.
+    pub fee: u64,
+    /// Return data set by the last program to call `set_return_data`.
+    pub return_data: TransactionReturnData,
+    /// Instructions invoked through CPI, grouped by top-level instruction.
+    pub inner_instructions: InnerInstructionsList,
+    /// The transaction's accounts, including those loaded from lookup tables, which
+    /// the `program_id_index` and `accounts` of `inner_instructions` index into.
+    pub account_keys: Vec<Pubkey>,
+}
+
+impl TransactionResult {
+    /// Builds a result from LiteSVM metadata for a transaction that was charged `fee` lamports.
+    pub(crate) fn from_metadata(metadata: TransactionMetadata, fee: u64, account_keys: Vec<Pubkey>) -> Self {
+        Self {
+            signature: metadata.signature,
+            logs: metadata.logs,
+            compute_units_consumed: metadata.compute_units_consumed,
+            fee,
+            return_data: metadata.return_data,
+            inner_instructions: metadata.inner_instructions,
+            account_keys,
+        }
+    }
+
+    /// Parses the logs into one [`ProgramInvocation`] per top-level instruction, with CPIs nested inside.
+    pub fn invocations(&self) -> Vec<ProgramInvocation> {
+        parse_program_logs(&self.logs)
+    }
+
+    /// Returns the logs as an indented call trace of program invocations.
+    pub fn call_trace(&self) -> String {
+        format_call_trace(&self.logs)
+    }
+
+    /// Returns the compute units consumed by each top-level instruction, in order.
+    ///
+    /// Builtin programs such as the System program don't log their compute usage, so
+    /// their instructions are reported as consuming zero compute units.
+    ///
+    /// # Errors
+    ///
+    /// Instructions are matched to the invocations in the logs by position, so this
+    /// returns [`SolanaKiteError::DeserializationFailed`] rather than misattributing
+    /// compute units when the logs were truncated, or when they don't have one
+    /// invocation per top-level instruction, as with precompile instructions, which
+    /// aren't logged.
+    pub fn instruction_compute_units(&self) -> Result<Vec<u64>, SolanaKiteError> {
+        if self.logs.iter().any(|log| log == "Log truncated") {
+            return Err(SolanaKiteError::DeserializationFailed(
+                "Can't attribute compute units to instructions, the logs were truncated".to_string(),
+            ));
+        }
+        let invocations = self.invocations();
+        if invocations.len() != self.inner_instructions.len() {
+            return Err(SolanaKiteError::DeserializationFailed(format!(
+                "Can't attribute compute units to instructions, the logs have {} top-level invocations for {} instructions",
+                invocations.len(),
+                self.inner_instructions.len()
+            )));
+        }
+        Ok(invocations
+            .iter()
+            .map(|invocation| invocation.compute_units_consumed.unwrap_or(0))
+            .collect())
+    }
+}
+
+/// Compute budget settings for a transaction.
+///
+/// Each setting that is `Some` adds the matching `ComputeBudget` instruction to the
+/// start of the transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudgetOptions {
+    /// Maximum compute units the transaction may consume.
+    pub compute_unit_limit: Option<u32>,
+    /// Priority fee, in micro-lamports per compute unit.
+    pub compute_unit_price: Option<u64>,
+    /// Heap size to request for the transaction's programs, in bytes.
+    pub heap_frame_bytes: Option<u32>,
+}
+
+impl ComputeBudgetOptions {
+    /// Returns the `ComputeBudget` instructions for these settings.
+    pub fn instructions(&self) -> Vec<solana_instruction::Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(units) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        if let Some(micro_lamports) = self.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(micro_lamports));
+        }
+        if let Some(bytes) = self.heap_frame_bytes {
+            instructions.push(ComputeBudgetInstruction::request_heap_frame(bytes));
+        }
+        instructions
+    }
+}
+
+/// The outcome of a simulated transaction, whose effects were not committed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationResult {
+    /// What executing the transaction would have produced.
+    pub result: TransactionResult,
+    /// The state every writable account in the transaction would have afterwards.
+    pub post_accounts: Vec<(Pubkey, Account)>,
+}
+
+impl SimulationResult {
+    /// Returns the state the given account would have after the transaction.
+    pub fn post_account(&self, address: &Pubkey) -> Option<&Account> {
+        self.post_accounts
+            .iter()
+            .find(|(account_address, _)| account_address == address)
+            .map(|(_, account)| account)
+    }
+}
 
 /// Sends a transaction built from a vector of instructions.
 ///
@@ -21,11 +167,14 @@ use solana_transaction::Transaction;
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on successful transaction, or a [`SolanaKiteError`] on failure.
+/// Returns a [`TransactionResult`] describing the executed transaction, or a
+/// [`SolanaKiteError`] on failure.
 ///
 /// # Errors
 ///
-/// This function will return an error if the transaction fails to send or execute.
+/// This function will return [`SolanaKiteError::TransactionExecutionFailed`] if the
+/// transaction fails to send or execute, or [`SolanaKiteError::AnchorError`] if it
+/// failed with an error logged by an Anchor program.
 ///
 /// # Example
 ///
@@ -38,13 +187,14 @@ use solana_transaction::Transaction;
 /// let mut litesvm = LiteSVM::new();
 /// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
 /// let instructions = vec![]; // Your instructions here
-/// 
-/// send_transaction_from_instructions(
+///
+/// let result = send_transaction_from_instructions(
 ///     &mut litesvm,
 ///     instructions,
 ///     &[&payer],
 ///     &payer.pubkey(),
 /// )?;
+/// println!("Consumed {} compute units", result.compute_units_consumed);
 /// # Ok(())
 /// # }
 /// ```
@@ -53,14 +203,659 @@ pub fn send_transaction_from_instructions(
     instructions: Vec<solana_instruction::Instruction>,
     signers: &[&Keypair],
     fee_payer: &Pubkey,
-) -> Result<(), SolanaKiteError> {
+) -> Result<TransactionResult, SolanaKiteError> {
+    let transaction = build_transaction(litesvm, &instructions, signers, fee_payer);
+    send_transaction(litesvm, transaction)
+}
+
+/// Sends a transaction with compute budget settings.
+///
+/// This works like [`send_transaction_from_instructions`], but first adds the
+/// `ComputeBudget` instructions for `compute_budget` to the start of the transaction.
+/// Because those instructions come first, instruction indexes in the result and in
+/// errors count them too.
+///
+/// LiteSVM doesn't charge priority fees, so `compute_unit_price` doesn't change the
+/// fee, but programs that read the compute budget will still see it.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `instructions` - Vector of instructions to include in the transaction
+/// * `signers` - Array of keypairs that will sign the transaction
+/// * `fee_payer` - Public key of the account that will pay transaction fees
+/// * `compute_budget` - Compute unit limit, price and heap size for the transaction
+///
+/// # Returns
+///
+/// Returns a [`TransactionResult`] describing the executed transaction, or a
+/// [`SolanaKiteError`] on failure.
+///
+/// # Errors
+///
+/// This function will return the same errors as [`send_transaction_from_instructions`],
+/// including when the transaction exceeds its compute unit limit.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{send_transaction_with_compute_budget, create_wallet, ComputeBudgetOptions};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let instructions = vec![]; // Your instructions here
+///
+/// send_transaction_with_compute_budget(
+///     &mut litesvm,
+///     instructions,
+///     &[&payer],
+///     &payer.pubkey(),
+///     &ComputeBudgetOptions {
+///         compute_unit_limit: Some(200_000),
+///         compute_unit_price: Some(1_000),
+///         ..Default::default()
+///     },
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn send_transaction_with_compute_budget(
+    litesvm: &mut LiteSVM,
+    instructions: Vec<solana_instruction::Instruction>,
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+    compute_budget: &ComputeBudgetOptions,
+) -> Result<TransactionResult, SolanaKiteError> {
+    let mut all_instructions = compute_budget.instructions();
+    all_instructions.extend(instructions);
+    send_transaction_from_instructions(litesvm, all_instructions, signers, fee_payer)
+}
+
+/// Sends a v0 versioned transaction that loads accounts from address lookup tables.
+///
+/// Any account used by the instructions that appears in one of the lookup tables is
+/// referenced through the table rather than listed in the message, so a transaction
+/// can use far more accounts than a legacy transaction allows.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `instructions` - Vector of instructions to include in the transaction
+/// * `signers` - Array of keypairs that will sign the transaction
+/// * `fee_payer` - Public key of the account that will pay transaction fees
+/// * `lookup_tables` - Addresses of the lookup tables the message may use
+///
+/// # Returns
+///
+/// Returns a [`TransactionResult`] describing the executed transaction, or a
+/// [`SolanaKiteError`] on failure.
+///
+/// # Errors
+///
+/// This function will return [`SolanaKiteError::MissingSigners`] if a required signer
+/// isn't in `signers`, [`SolanaKiteError::TransactionBuildFailed`] if the message
+/// can't be compiled or signed, or an error if a lookup table can't be read or the
+/// transaction fails to execute.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{
+///     create_lookup_table, create_wallet, extend_lookup_table,
+///     send_versioned_transaction_from_instructions,
+/// };
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let recipient = Pubkey::new_unique();
+///
+/// let lookup_table = create_lookup_table(&mut litesvm, &payer.pubkey(), &payer)?;
+/// extend_lookup_table(&mut litesvm, &lookup_table, &payer, vec![recipient])?;
+///
+/// let transfer_instruction =
+///     solana_program::system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+/// send_versioned_transaction_from_instructions(
+///     &mut litesvm,
+///     vec![transfer_instruction],
+///     &[&payer],
+///     &payer.pubkey(),
+///     &[lookup_table],
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn send_versioned_transaction_from_instructions(
+    litesvm: &mut LiteSVM,
+    instructions: Vec<solana_instruction::Instruction>,
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+    lookup_tables: &[Pubkey],
+) -> Result<TransactionResult, SolanaKiteError> {
+    let lookup_table_accounts = load_lookup_table_accounts(litesvm, lookup_tables)?;
+    let message = v0::Message::try_compile(
+        fee_payer,
+        &instructions,
+        &lookup_table_accounts,
+        litesvm.latest_blockhash(),
+    )
+    .map_err(|e| SolanaKiteError::TransactionBuildFailed(format!("Failed to compile v0 message: {}", e)))?;
+
+    let provided_signers: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+    let missing_signers: Vec<Pubkey> = message.account_keys[..message.header.num_required_signatures as usize]
+        .iter()
+        .filter(|pubkey| !provided_signers.contains(pubkey))
+        .copied()
+        .collect();
+    if !missing_signers.is_empty() {
+        return Err(SolanaKiteError::MissingSigners(missing_signers));
+    }
+
+    let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+        .map_err(|e| SolanaKiteError::TransactionBuildFailed(format!("Failed to sign versioned transaction: {}", e)))?;
+
+    send_transaction(litesvm, transaction)
+}
+
+/// Simulates a transaction built from a vector of instructions without committing it.
+///
+/// This builds and signs the transaction exactly like [`send_transaction_from_instructions`],
+/// but executes it with LiteSVM's `simulate_transaction`, so no account changes are
+/// written. Use it to preview an instruction's effects before deciding to send it.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `instructions` - Vector of instructions to include in the transaction
+/// * `signers` - Array of keypairs that will sign the transaction
+/// * `fee_payer` - Public key of the account that would pay transaction fees
+///
+/// # Returns
+///
+/// Returns a [`SimulationResult`] with the logs, compute units, return data and the
+/// post-transaction state of every writable account.
+///
+/// # Errors
+///
+/// This function will return the same errors as [`send_transaction_from_instructions`]
+/// if the simulated transaction fails.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{simulate_transaction_from_instructions, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let recipient = Pubkey::new_unique();
+/// let transfer_instruction =
+///     solana_program::system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+///
+/// let simulation = simulate_transaction_from_instructions(
+///     &litesvm,
+///     vec![transfer_instruction],
+///     &[&payer],
+///     &payer.pubkey(),
+/// )?;
+///
+/// // The recipient would be funded, but nothing has been committed yet
+/// assert_eq!(simulation.post_account(&recipient).unwrap().lamports, 1_000_000);
+/// assert!(litesvm.get_account(&recipient).is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub fn simulate_transaction_from_instructions(
+    litesvm: &LiteSVM,
+    instructions: Vec<solana_instruction::Instruction>,
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+) -> Result<SimulationResult, SolanaKiteError> {
+    let transaction = build_transaction(litesvm, &instructions, signers, fee_payer);
+    simulate_transaction(litesvm, transaction)
+}
+
+/// Calls a read-only "view" instruction and deserializes the data it returns.
+///
+/// The instruction is simulated, so nothing is committed, and the data the program
+/// set with `set_return_data` is deserialized with Borsh into `T`.
+///
+/// # Arguments
+///
+/// * `litesvm` - Reference to the LiteSVM instance
+/// * `instruction` - The view instruction to call
+/// * `signers` - Array of keypairs that will sign the transaction
+/// * `fee_payer` - Public key of the account that would pay transaction fees
+///
+/// # Returns
+///
+/// Returns the deserialized return data.
+///
+/// # Errors
+///
+/// This function will return an error if the simulated transaction fails, if the
+/// instruction's program didn't set the return data, or if the return data can't be
+/// deserialized into `T`.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{call_view_instruction, create_token_mint, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &payer, 6, None, None, &spl_token::ID)?;
+///
+/// // The Token program returns the size of a token account for this mint
+/// let instruction = spl_token::instruction::get_account_data_size(&spl_token::id(), &mint)?;
+/// let size: u64 = call_view_instruction(&litesvm, instruction, &[&payer], &payer.pubkey())?;
+/// assert_eq!(size, 165);
+/// # Ok(())
+/// # }
+/// ```
+pub fn call_view_instruction<T: BorshDeserialize>(
+    litesvm: &LiteSVM,
+    instruction: solana_instruction::Instruction,
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+) -> Result<T, SolanaKiteError> {
+    let program_id = instruction.program_id;
+    let simulation = simulate_transaction_from_instructions(litesvm, vec![instruction], signers, fee_payer)?;
+    let return_data = simulation.result.return_data;
+
+    if return_data.program_id != program_id {
+        return Err(SolanaKiteError::DeserializationFailed(format!(
+            "Expected return data from program {}, but it was set by {}",
+            program_id, return_data.program_id
+        )));
+    }
+
+    T::try_from_slice(&return_data.data).map_err(|e| {
+        SolanaKiteError::DeserializationFailed(format!("Failed to deserialize return data from program {}: {}", program_id, e))
+    })
+}
+
+/// Sends a signed transaction and converts LiteSVM's outcome into Kite's types.
+pub(crate) fn send_transaction(
+    litesvm: &mut LiteSVM,
+    transaction: impl Into<VersionedTransaction>,
+) -> Result<TransactionResult, SolanaKiteError> {
+    let transaction = transaction.into();
+    let sanitized = sanitize_transaction(litesvm, &transaction);
+    let fee = transaction_fee(litesvm, sanitized.as_ref());
+    let account_keys = transaction_account_keys(&transaction, sanitized.as_ref());
+
+    litesvm
+        .send_transaction(transaction)
+        .map(|metadata| TransactionResult::from_metadata(metadata, fee, account_keys))
+        .map_err(SolanaKiteError::from)
+}
+
+/// Simulates a signed transaction and converts LiteSVM's outcome into Kite's types.
+pub(crate) fn simulate_transaction(
+    litesvm: &LiteSVM,
+    transaction: impl Into<VersionedTransaction>,
+) -> Result<SimulationResult, SolanaKiteError> {
+    let transaction = transaction.into();
+    let sanitized = sanitize_transaction(litesvm, &transaction);
+    let fee = transaction_fee(litesvm, sanitized.as_ref());
+    let account_keys = transaction_account_keys(&transaction, sanitized.as_ref());
+
+    let simulated = litesvm
+        .simulate_transaction(transaction)
+        .map_err(SolanaKiteError::from)?;
+
+    Ok(SimulationResult {
+        result: TransactionResult::from_metadata(simulated.meta, fee, account_keys),
+        post_accounts: simulated
+            .post_accounts
+            .into_iter()
+            .map(|(address, account)| (address, account.into()))
+            .collect(),
+    })
+}
+
+/// Sanitizes a transaction against LiteSVM's accounts, loading its lookup table addresses.
+///
+/// Returns `None` for transactions that don't sanitize, which LiteSVM rejects.
+fn sanitize_transaction(litesvm: &LiteSVM, transaction: &VersionedTransaction) -> Option<SanitizedTransaction> {
+    SanitizedTransaction::try_create(
+        transaction.clone(),
+        MessageHash::Compute,
+        Some(false),
+        litesvm.accounts_db(),
+        &ReservedAccountKeys::empty_key_set(),
+    )
+    .ok()
+}
+
+/// Returns the transaction's accounts in the order instructions index them.
+fn transaction_account_keys(
+    transaction: &VersionedTransaction,
+    sanitized: Option<&SanitizedTransaction>,
+) -> Vec<Pubkey> {
+    match sanitized {
+        Some(sanitized) => sanitized.message().account_keys().iter().copied().collect(),
+        None => transaction.message.static_account_keys().to_vec(),
+    }
+}
+
+/// Calculates the fee LiteSVM charges for a transaction, the same way LiteSVM does.
+///
+/// Precompile instructions (ed25519, secp256k1 and secp256r1) are charged for each
+/// signature they verify, on top of the transaction's own signatures. Secp256r1
+/// signatures are only charged for when LiteSVM's feature set enables that precompile.
+fn transaction_fee(litesvm: &LiteSVM, sanitized: Option<&SanitizedTransaction>) -> u64 {
+    // LiteSVM rejects transactions that don't sanitize before charging them anything
+    let Some(sanitized) = sanitized else {
+        return 0;
+    };
+
+    // LiteSVM always charges with the default fee structure
+    calculate_fee(
+        sanitized,
+        false,
+        FeeStructure::default().lamports_per_signature,
+        0,
+        fee_features(litesvm),
+    )
+}
+
+/// Returns the fee features of LiteSVM's feature set.
+///
+/// `LiteSVM::get_feature_set` only exists behind litesvm's `internal-test` feature,
+/// which doesn't compile in litesvm 0.7. LiteSVM loads the secp256r1 precompile, the
+/// only fee feature, exactly when its feature is active, so check for that instead.
+fn fee_features(litesvm: &LiteSVM) -> FeeFeatures {
+    let secp256r1_precompile = litesvm.get_account(&solana_sdk_ids::secp256r1_program::id());
+    FeeFeatures {
+        enable_secp256r1_precompile: secp256r1_precompile
+            .is_some_and(|account| account.owner == solana_sdk_ids::native_loader::id()),
+    }
+}
+
+/// Builds and signs a legacy transaction using the latest blockhash.
+fn build_transaction(
+    litesvm: &LiteSVM,
+    instructions: &[solana_instruction::Instruction],
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+) -> Transaction {
     let recent_blockhash = litesvm.latest_blockhash();
-    let message = Message::new(&instructions, Some(fee_payer));
+    let message = Message::new(instructions, Some(fee_payer));
     let mut transaction = Transaction::new_unsigned(message);
     transaction.sign(signers, recent_blockhash);
-    
-    litesvm
-        .send_transaction(transaction)
-        .map(|_| ())
-        .map_err(|e| SolanaKiteError::TransactionFailed(format!("{:?}", e)))
-}
\ No newline at end of file
+    transaction
+}
+
+/// Asserts that a transaction fails with the given custom program error code.
+///
+/// This sends a transaction built from the instructions, like
+/// [`send_transaction_from_instructions`], and checks that one of its instructions
+/// returned `InstructionError::Custom(expected_code)`.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `instructions` - Vector of instructions to include in the transaction
+/// * `signers` - Array of keypairs that will sign the transaction
+/// * `fee_payer` - Public key of the account that will pay transaction fees
+/// * `expected_code` - The custom error code the program should return
+///
+/// # Panics
+///
+/// Panics if the transaction succeeds or fails with a different error. The panic
+/// message shows the expected and actual errors along with the program logs.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{
+///     assert_transaction_fails_with_custom_error, create_associated_token_account,
+///     create_token_mint, create_wallet,
+/// };
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let user = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint, &user, &spl_token::ID)?;
+///
+/// // Only the mint authority can mint, so this fails with TokenError::OwnerMismatch (4)
+/// let mint_to_instruction = spl_token::instruction::mint_to(
+///     &spl_token::id(),
+///     &mint,
+///     &token_account,
+///     &user.pubkey(),
+///     &[],
+///     1_000_000,
+/// )?;
+///
+/// assert_transaction_fails_with_custom_error(
+///     &mut litesvm,
+///     vec![mint_to_instruction],
+///     &[&user],
+///     &user.pubkey(),
+///     4,
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn assert_transaction_fails_with_custom_error(
+    litesvm: &mut LiteSVM,
+    instructions: Vec<solana_instruction::Instruction>,
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+    expected_code: u32,
+) {
+    let expected = format!("custom program error: {:#x} ({})", expected_code, expected_code);
+    let failure = expect_failure(litesvm, instructions, signers, fee_payer, &expected);
+    if failure.custom_error_code != Some(expected_code) {
+        panic_with_unexpected_error(&expected, &failure);
+    }
+}
+
+/// Asserts that the instruction at `expected_index` fails with the given instruction error.
+///
+/// This sends a transaction built from the instructions, like
+/// [`send_transaction_from_instructions`], and checks both which instruction failed
+/// and how it failed.
+///
+/// # Arguments
+///
+/// * `litesvm` - Mutable reference to the LiteSVM instance
+/// * `instructions` - Vector of instructions to include in the transaction
+/// * `signers` - Array of keypairs that will sign the transaction
+/// * `fee_payer` - Public key of the account that will pay transaction fees
+/// * `expected_index` - Index of the instruction that should fail
+/// * `expected_error` - The error the instruction should fail with
+///
+/// # Panics
+///
+/// Panics if the transaction succeeds or fails with a different error or at a
+/// different instruction. The panic message shows the expected and actual errors
+/// along with the program logs.
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{assert_instruction_error, create_wallet};
+/// use litesvm::LiteSVM;
+/// use solana_instruction::error::InstructionError;
+/// use solana_pubkey::Pubkey;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
+///
+/// // Transferring more lamports than the payer holds fails in the System program
+/// let transfer_instruction = solana_program::system_instruction::transfer(
+///     &payer.pubkey(),
+///     &Pubkey::new_unique(),
+///     10_000_000_000,
+/// );
+///
+/// assert_instruction_error(
+///     &mut litesvm,
+///     vec![transfer_instruction],
+///     &[&payer],
+///     &payer.pubkey(),
+///     0,
+///     InstructionError::Custom(1), // SystemError::ResultWithNegativeLamports
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn assert_instruction_error(
+    litesvm: &mut LiteSVM,
+    instructions: Vec<solana_instruction::Instruction>,
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+    expected_index: u8,
+    expected_error: InstructionError,
+) {
+    let expected = format!("instruction {} failed with {:?}", expected_index, expected_error);
+    let failure = expect_failure(litesvm, instructions, signers, fee_payer, &expected);
+    if failure.instruction_index != Some(expected_index)
+        || failure.instruction_error() != Some(&expected_error)
+    {
+        panic_with_unexpected_error(&expected, &failure);
+    }
+}
+
+/// Asserts that an instruction consumed no more than `max_compute_units` compute units.
+///
+/// Use this to catch compute unit regressions in CI.
+///
+/// # Arguments
+///
+/// * `result` - The result of the executed transaction
+/// * `instruction_index` - Index of the top-level instruction to check
+/// * `max_compute_units` - The most compute units the instruction may consume
+///
+/// # Panics
+///
+/// Panics if the instruction consumed more than `max_compute_units`, if the
+/// transaction has no instruction at `instruction_index`, or if the logs can't be
+/// matched to the instructions, see [`TransactionResult::instruction_compute_units`].
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{
+///     assert_instruction_compute_units, create_associated_token_account, create_wallet,
+///     create_token_mint, send_transaction_from_instructions,
+/// };
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account =
+///     create_associated_token_account(&mut litesvm, &mint_authority.pubkey(), &mint, &mint_authority, &spl_token::ID)?;
+///
+/// let mint_to_instruction = spl_token::instruction::mint_to(
+///     &spl_token::id(),
+///     &mint,
+///     &token_account,
+///     &mint_authority.pubkey(),
+///     &[],
+///     1_000_000,
+/// )?;
+/// let result = send_transaction_from_instructions(
+///     &mut litesvm,
+///     vec![mint_to_instruction],
+///     &[&mint_authority],
+///     &mint_authority.pubkey(),
+/// )?;
+///
+/// assert_instruction_compute_units(&result, 0, 10_000);
+/// # Ok(())
+/// # }
+/// ```
+pub fn assert_instruction_compute_units(
+    result: &TransactionResult,
+    instruction_index: usize,
+    max_compute_units: u64,
+) {
+    let compute_units = result
+        .instruction_compute_units()
+        .unwrap_or_else(|error| panic!("{}\n{}", error, format_logs(&result.logs)));
+    let consumed = *compute_units.get(instruction_index).unwrap_or_else(|| {
+        panic!(
+            "Transaction has no instruction {} (it has {} instructions)",
+            instruction_index,
+            compute_units.len()
+        )
+    });
+    assert!(
+        consumed <= max_compute_units,
+        "Instruction {} consumed {} compute units, more than the maximum of {}\n{}",
+        instruction_index,
+        consumed,
+        max_compute_units,
+        format_logs(&result.logs)
+    );
+}
+
+/// Sends the transaction and returns its failure, panicking if it succeeds.
+fn expect_failure(
+    litesvm: &mut LiteSVM,
+    instructions: Vec<solana_instruction::Instruction>,
+    signers: &[&Keypair],
+    fee_payer: &Pubkey,
+    expected: &str,
+) -> TransactionFailure {
+    match send_transaction_from_instructions(litesvm, instructions, signers, fee_payer) {
+        Ok(result) => panic!(
+            "Expected transaction to fail, but it succeeded\n  expected: {}\n    actual: success\n{}",
+            expected,
+            format_logs(&result.logs)
+        ),
+        Err(error) => match error.transaction_failure() {
+            Some(failure) => failure.clone(),
+            None => panic!(
+                "Transaction failed with an unexpected error\n  expected: {}\n    actual: {}",
+                expected, error
+            ),
+        },
+    }
+}
+
+fn panic_with_unexpected_error(expected: &str, failure: &TransactionFailure) -> ! {
+    panic!(
+        "Transaction failed with an unexpected error\n  expected: {}\n    actual: {}\n{}",
+        expected,
+        failure.error,
+        format_logs(&failure.logs)
+    )
+}
+
+/// Formats the call trace followed by the raw logs, which also include any lines
+/// logged outside a program invocation, such as runtime errors.
+fn format_logs(logs: &[String]) -> String {
+    let mut formatted = format!("Call trace:\n{}Logs:", format_call_trace(logs));
+    for log in logs {
+        formatted.push_str("\n  ");
+        formatted.push_str(log);
+    }
+    formatted
+}
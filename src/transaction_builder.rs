@@ -0,0 +1,213 @@
+//! A builder for composing transactions with multiple signers.
+
+use crate::error::SolanaKiteError;
+use crate::lookup_table::load_lookup_table_accounts;
+use crate::transaction::{
+    send_transaction, simulate_transaction, ComputeBudgetOptions, SimulationResult,
+    TransactionResult,
+};
+use litesvm::LiteSVM;
+use solana_instruction::Instruction;
+use solana_message::{v0, Message, VersionedMessage};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::versioned::VersionedTransaction;
+
+/// Builds, signs and sends a transaction.
+///
+/// Instructions and signers are collected as you go. When the transaction is built,
+/// the builder works out which accounts must sign from the fee payer and each
+/// instruction's `AccountMeta::is_signer` flags, signs with just those signers (each
+/// once), and reports any required signers that weren't provided. Any [`Signer`]
+/// implementation can sign, not only [`Keypair`](solana_keypair::Keypair).
+///
+/// # Example
+///
+/// ```rust
+/// use solana_kite::{create_associated_token_account, create_token_mint, create_wallet, TransactionBuilder};
+/// use litesvm::LiteSVM;
+/// use solana_signer::Signer;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut litesvm = LiteSVM::new();
+/// let payer = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint_authority = create_wallet(&mut litesvm, 1_000_000_000)?;
+/// let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID)?;
+/// let token_account = create_associated_token_account(&mut litesvm, &payer.pubkey(), &mint, &payer, &spl_token::ID)?;
+///
+/// let mint_to_instruction = spl_token::instruction::mint_to(
+///     &spl_token::id(),
+///     &mint,
+///     &token_account,
+///     &mint_authority.pubkey(),
+///     &[],
+///     1_000_000,
+/// )?;
+///
+/// let result = TransactionBuilder::new(&payer)
+///     .instruction(mint_to_instruction)
+///     .signer(&mint_authority)
+///     .send(&mut litesvm)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TransactionBuilder<'a> {
+    fee_payer: &'a dyn Signer,
+    instructions: Vec<Instruction>,
+    signers: Vec<&'a dyn Signer>,
+    compute_budget: ComputeBudgetOptions,
+    lookup_tables: Vec<Pubkey>,
+}
+
+impl<'a> TransactionBuilder<'a> {
+    /// Creates a builder for a transaction paid for, and signed by, `fee_payer`.
+    pub fn new(fee_payer: &'a dyn Signer) -> Self {
+        Self {
+            fee_payer,
+            instructions: Vec::new(),
+            signers: Vec::new(),
+            compute_budget: ComputeBudgetOptions::default(),
+            lookup_tables: Vec::new(),
+        }
+    }
+
+    /// Adds an instruction to the transaction.
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Adds several instructions to the transaction, in order.
+    pub fn instructions(mut self, instructions: impl IntoIterator<Item = Instruction>) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// Adds a signer. Signers that no instruction needs are ignored.
+    pub fn signer(mut self, signer: &'a dyn Signer) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    /// Adds several signers. Signers that no instruction needs are ignored.
+    pub fn signers(mut self, signers: impl IntoIterator<Item = &'a dyn Signer>) -> Self {
+        self.signers.extend(signers);
+        self
+    }
+
+    /// Sets the compute budget, which adds `ComputeBudget` instructions to the start of the transaction.
+    pub fn compute_budget(mut self, compute_budget: ComputeBudgetOptions) -> Self {
+        self.compute_budget = compute_budget;
+        self
+    }
+
+    /// Sends the transaction as a v0 versioned transaction that may use these lookup tables.
+    pub fn lookup_tables(mut self, lookup_tables: &[Pubkey]) -> Self {
+        self.lookup_tables.extend_from_slice(lookup_tables);
+        self
+    }
+
+    /// Returns the accounts that must sign: the fee payer, then every account an
+    /// instruction marks as a signer, each listed once.
+    pub fn required_signers(&self) -> Vec<Pubkey> {
+        let mut required_signers = vec![self.fee_payer.pubkey()];
+        let signer_accounts = self
+            .instructions
+            .iter()
+            .flat_map(|instruction| &instruction.accounts)
+            .filter(|account| account.is_signer);
+        for account in signer_accounts {
+            if !required_signers.contains(&account.pubkey) {
+                required_signers.push(account.pubkey);
+            }
+        }
+        required_signers
+    }
+
+    /// Returns the required signers that haven't been provided.
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        let provided: Vec<Pubkey> = self.provided_signers().map(|signer| signer.pubkey()).collect();
+        self.required_signers()
+            .into_iter()
+            .filter(|pubkey| !provided.contains(pubkey))
+            .collect()
+    }
+
+    /// Builds and signs the transaction without sending it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolanaKiteError::MissingSigners`] if any required signer wasn't
+    /// provided, [`SolanaKiteError::TransactionBuildFailed`] if the message can't be
+    /// compiled or signed, or an error if a lookup table can't be read.
+    pub fn build(&self, litesvm: &LiteSVM) -> Result<VersionedTransaction, SolanaKiteError> {
+        let missing_signers = self.missing_signers();
+        if !missing_signers.is_empty() {
+            return Err(SolanaKiteError::MissingSigners(missing_signers));
+        }
+
+        let mut instructions = self.compute_budget.instructions();
+        instructions.extend(self.instructions.iter().cloned());
+
+        let fee_payer = self.fee_payer.pubkey();
+        let recent_blockhash = litesvm.latest_blockhash();
+        let message = if self.lookup_tables.is_empty() {
+            VersionedMessage::Legacy(Message::new_with_blockhash(
+                &instructions,
+                Some(&fee_payer),
+                &recent_blockhash,
+            ))
+        } else {
+            let lookup_table_accounts = load_lookup_table_accounts(litesvm, &self.lookup_tables)?;
+            let message = v0::Message::try_compile(
+                &fee_payer,
+                &instructions,
+                &lookup_table_accounts,
+                recent_blockhash,
+            )
+            .map_err(|e| SolanaKiteError::TransactionBuildFailed(format!("Failed to compile v0 message: {}", e)))?;
+            VersionedMessage::V0(message)
+        };
+
+        // Sign with each required signer exactly once
+        let required_signers = self.required_signers();
+        let mut signers: Vec<&dyn Signer> = Vec::with_capacity(required_signers.len());
+        for signer in self.provided_signers() {
+            let pubkey = signer.pubkey();
+            if required_signers.contains(&pubkey) && !signers.iter().any(|added| added.pubkey() == pubkey) {
+                signers.push(signer);
+            }
+        }
+
+        VersionedTransaction::try_new(message, &signers)
+            .map_err(|e| SolanaKiteError::TransactionBuildFailed(format!("Failed to sign transaction: {}", e)))
+    }
+
+    /// Builds, signs and sends the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors from [`build`](Self::build), or the same errors as
+    /// [`send_transaction_from_instructions`](crate::send_transaction_from_instructions)
+    /// if the transaction fails.
+    pub fn send(self, litesvm: &mut LiteSVM) -> Result<TransactionResult, SolanaKiteError> {
+        let transaction = self.build(litesvm)?;
+        send_transaction(litesvm, transaction)
+    }
+
+    /// Builds, signs and simulates the transaction without committing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors from [`build`](Self::build), or the same errors as
+    /// [`simulate_transaction_from_instructions`](crate::simulate_transaction_from_instructions)
+    /// if the simulated transaction fails.
+    pub fn simulate(self, litesvm: &LiteSVM) -> Result<SimulationResult, SolanaKiteError> {
+        let transaction = self.build(litesvm)?;
+        simulate_transaction(litesvm, transaction)
+    }
+
+    fn provided_signers(&self) -> impl Iterator<Item = &'a dyn Signer> + '_ {
+        std::iter::once(self.fee_payer).chain(self.signers.iter().copied())
+    }
+}
@@ -5,7 +5,23 @@ use solana_kite::{
     create_wallet, create_wallets, create_token_mint, create_associated_token_account,
     mint_tokens_to_account, get_token_account_balance, assert_token_balance,
     send_transaction_from_instructions, get_pda_and_bump, seeds, Seed, check_account_is_closed,
+    SolanaKiteError, assert_transaction_fails_with_custom_error, assert_instruction_error,
+    simulate_transaction_from_instructions, send_transaction_with_compute_budget,
+    assert_instruction_compute_units, ComputeBudgetOptions,
+    create_lookup_table, extend_lookup_table, freeze_lookup_table, deactivate_lookup_table,
+    get_lookup_table_addresses, send_versioned_transaction_from_instructions, TransactionBuilder,
+    InvocationOutcome, call_view_instruction, MintBuilder, transfer_tokens, transfer_tokens_checked,
+    burn_tokens, approve_delegate, approve_delegate_checked, revoke_delegate, freeze_token_account,
+    thaw_token_account, close_token_account, set_token_authority, get_mint, get_token_account,
+    set_token_balance, set_mint_state, wrap_sol, unwrap_sol, create_multisig, TokenAuthority,
+    to_ui_amount, from_ui_amount, assert_token_ui_balance, create_nft, create_collection_nft,
+    deploy_token_metadata_program, create_associated_token_account_idempotent, create_token_account,
+    deploy_upgradeable_program, upgrade_program, set_upgrade_authority, get_upgrade_authority,
+    deploy_program, deploy_program_from_bytes, deploy_anchor_workspace,
 };
+use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+use mpl_token_metadata::types::CollectionDetails;
+use solana_instruction::error::InstructionError;
 use solana_pubkey::Pubkey;
 use solana_signer::Signer;
 
@@ -37,7 +53,7 @@ fn test_token_operations() {
     let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
     
     // Create token mint
-    let mint = create_token_mint(&mut litesvm, &mint_authority, 9, None).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 9, None, None, &spl_token::ID).unwrap();
     
     // Create associated token account
     let token_account = create_associated_token_account(
@@ -45,10 +61,11 @@ fn test_token_operations() {
         &user.pubkey(),
         &mint,
         &user,
+        &spl_token::ID,
     ).unwrap();
     
     // Check initial balance
-    let initial_balance = get_token_account_balance(&litesvm, &token_account).unwrap();
+    let initial_balance = get_token_account_balance(&litesvm, &token_account, &spl_token::ID).unwrap();
     assert_eq!(initial_balance, 0);
     
     // Mint tokens
@@ -59,16 +76,538 @@ fn test_token_operations() {
         &token_account,
         mint_amount,
         &mint_authority,
+        &spl_token::ID,
     ).unwrap();
     
     // Verify balance
-    let final_balance = get_token_account_balance(&litesvm, &token_account).unwrap();
+    let final_balance = get_token_account_balance(&litesvm, &token_account, &spl_token::ID).unwrap();
     assert_eq!(final_balance, mint_amount);
     
     // Test assertion helper
     assert_token_balance(&litesvm, &token_account, mint_amount, "Balance should match");
 }
 
+#[test]
+fn test_token_2022_operations() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token_2022::ID).unwrap();
+    assert_eq!(litesvm.get_account(&mint).unwrap().owner, spl_token_2022::ID);
+
+    let token_account = create_associated_token_account(
+        &mut litesvm,
+        &user.pubkey(),
+        &mint,
+        &user,
+        &spl_token_2022::ID,
+    ).unwrap();
+    assert_eq!(
+        token_account,
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            &user.pubkey(),
+            &mint,
+            &spl_token_2022::ID,
+        )
+    );
+
+    mint_tokens_to_account(&mut litesvm, &mint, &token_account, 5_000_000, &mint_authority, &spl_token_2022::ID).unwrap();
+    assert_eq!(get_token_account_balance(&litesvm, &token_account, &spl_token_2022::ID).unwrap(), 5_000_000);
+    assert_token_balance(&litesvm, &token_account, 5_000_000, "Token-2022 balance should match");
+
+    // Reading the account as the wrong token program fails
+    let error = get_token_account_balance(&litesvm, &token_account, &spl_token::ID).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+
+    // Only the two token programs are accepted
+    let error = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &Pubkey::new_unique()).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+}
+
+#[test]
+fn test_token_transfer_and_burn() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut litesvm = LiteSVM::new();
+        let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let alice = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let bob = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+        let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &token_program).unwrap();
+        let alice_account = create_associated_token_account(&mut litesvm, &alice.pubkey(), &mint, &alice, &token_program).unwrap();
+        let bob_account = create_associated_token_account(&mut litesvm, &bob.pubkey(), &mint, &bob, &token_program).unwrap();
+        mint_tokens_to_account(&mut litesvm, &mint, &alice_account, 1_000_000, &mint_authority, &token_program).unwrap();
+
+        transfer_tokens(&mut litesvm, &alice_account, &bob_account, 300_000, &alice, &token_program).unwrap();
+        let withheld =
+            transfer_tokens_checked(&mut litesvm, &mint, &alice_account, &bob_account, 200_000, &alice, &token_program).unwrap();
+        assert_eq!(withheld, 0);
+        burn_tokens(&mut litesvm, &mint, &bob_account, 100_000, &bob, &token_program).unwrap();
+
+        assert_token_balance(&litesvm, &alice_account, 500_000, "Alice should have sent half her tokens");
+        assert_token_balance(&litesvm, &bob_account, 400_000, "Bob should have burned some tokens");
+
+        // Only the owner can move tokens
+        let error = transfer_tokens(&mut litesvm, &alice_account, &bob_account, 1, &bob, &token_program).unwrap_err();
+        assert!(error.transaction_failure().is_some());
+    }
+}
+
+#[test]
+fn test_token_delegation_freeze_and_close() {
+    use spl_token_2022::instruction::AuthorityType;
+
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut litesvm = LiteSVM::new();
+        let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let alice = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let bob = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let delegate = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+        let mint = create_token_mint(
+            &mut litesvm,
+            &mint_authority,
+            6,
+            None,
+            Some(mint_authority.pubkey()),
+            &token_program,
+        ).unwrap();
+        let alice_account = create_associated_token_account(&mut litesvm, &alice.pubkey(), &mint, &alice, &token_program).unwrap();
+        let bob_account = create_associated_token_account(&mut litesvm, &bob.pubkey(), &mint, &bob, &token_program).unwrap();
+        mint_tokens_to_account(&mut litesvm, &mint, &alice_account, 1_000, &mint_authority, &token_program).unwrap();
+
+        // A delegate can move up to its allowance
+        approve_delegate(&mut litesvm, &alice_account, &delegate.pubkey(), 100, &alice, &token_program).unwrap();
+        transfer_tokens(&mut litesvm, &alice_account, &bob_account, 100, &delegate, &token_program).unwrap();
+        assert!(transfer_tokens(&mut litesvm, &alice_account, &bob_account, 1, &delegate, &token_program).is_err());
+
+        approve_delegate_checked(&mut litesvm, &mint, &alice_account, &delegate.pubkey(), 50, &alice, &token_program).unwrap();
+        revoke_delegate(&mut litesvm, &alice_account, &alice, &token_program).unwrap();
+        assert!(transfer_tokens(&mut litesvm, &alice_account, &bob_account, 1, &delegate, &token_program).is_err());
+
+        // Frozen accounts can't send tokens until thawed
+        freeze_token_account(&mut litesvm, &mint, &alice_account, &mint_authority, &token_program).unwrap();
+        assert!(transfer_tokens(&mut litesvm, &alice_account, &bob_account, 1, &alice, &token_program).is_err());
+        thaw_token_account(&mut litesvm, &mint, &alice_account, &mint_authority, &token_program).unwrap();
+        transfer_tokens(&mut litesvm, &alice_account, &bob_account, 900, &alice, &token_program).unwrap();
+
+        // Empty accounts can be closed
+        close_token_account(&mut litesvm, &alice_account, &alice.pubkey(), &alice, &token_program).unwrap();
+        check_account_is_closed(&litesvm, &alice_account, "Alice's token account should be closed");
+
+        // Handing over, then removing, the mint authority
+        let new_mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        set_token_authority(
+            &mut litesvm,
+            &mint,
+            AuthorityType::MintTokens,
+            Some(&new_mint_authority.pubkey()),
+            &mint_authority,
+            &token_program,
+        ).unwrap();
+        assert!(mint_tokens_to_account(&mut litesvm, &mint, &bob_account, 1, &mint_authority, &token_program).is_err());
+        mint_tokens_to_account(&mut litesvm, &mint, &bob_account, 1, &new_mint_authority, &token_program).unwrap();
+        set_token_authority(&mut litesvm, &mint, AuthorityType::MintTokens, None, &new_mint_authority, &token_program).unwrap();
+        assert!(mint_tokens_to_account(&mut litesvm, &mint, &bob_account, 1, &new_mint_authority, &token_program).is_err());
+        assert_token_balance(&litesvm, &bob_account, 1_001, "Bob should hold every token");
+    }
+}
+
+#[test]
+fn test_create_token_mint_with_freeze_authority() {
+    use spl_token_2022::state::AccountState;
+
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut litesvm = LiteSVM::new();
+        let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let freeze_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let owner = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+        let mint = create_token_mint(
+            &mut litesvm,
+            &mint_authority,
+            6,
+            None,
+            Some(freeze_authority.pubkey()),
+            &token_program,
+        ).unwrap();
+        assert_eq!(get_mint(&litesvm, &mint).unwrap().freeze_authority, Some(freeze_authority.pubkey()));
+
+        let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &token_program).unwrap();
+
+        // Only the freeze authority can freeze, not the mint authority
+        assert!(freeze_token_account(&mut litesvm, &mint, &token_account, &mint_authority, &token_program).is_err());
+        freeze_token_account(&mut litesvm, &mint, &token_account, &freeze_authority, &token_program).unwrap();
+        assert_eq!(get_token_account(&litesvm, &token_account).unwrap().state, AccountState::Frozen);
+
+        thaw_token_account(&mut litesvm, &mint, &token_account, &freeze_authority, &token_program).unwrap();
+        assert_eq!(get_token_account(&litesvm, &token_account).unwrap().state, AccountState::Initialized);
+    }
+}
+
+#[test]
+fn test_get_mint_and_token_account() {
+    use spl_token_2022::extension::{transfer_fee::TransferFeeConfig, ExtensionType};
+    use spl_token_2022::state::AccountState;
+
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let owner = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let delegate = Pubkey::new_unique();
+
+    let mint = create_token_mint(
+        &mut litesvm,
+        &mint_authority,
+        9,
+        None,
+        Some(mint_authority.pubkey()),
+        &spl_token::ID,
+    ).unwrap();
+    let token_account = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &owner, &spl_token::ID).unwrap();
+    mint_tokens_to_account(&mut litesvm, &mint, &token_account, 700, &mint_authority, &spl_token::ID).unwrap();
+    approve_delegate(&mut litesvm, &token_account, &delegate, 300, &owner, &spl_token::ID).unwrap();
+    freeze_token_account(&mut litesvm, &mint, &token_account, &mint_authority, &spl_token::ID).unwrap();
+
+    let mint_state = get_mint(&litesvm, &mint).unwrap();
+    assert_eq!(mint_state.token_program, spl_token::ID);
+    assert_eq!(mint_state.supply, 700);
+    assert_eq!(mint_state.decimals, 9);
+    assert_eq!(mint_state.mint_authority, Some(mint_authority.pubkey()));
+    assert_eq!(mint_state.freeze_authority, Some(mint_authority.pubkey()));
+    assert!(mint_state.extension_types.is_empty());
+
+    let account_state = get_token_account(&litesvm, &token_account).unwrap();
+    assert_eq!(account_state.mint, mint);
+    assert_eq!(account_state.owner, owner.pubkey());
+    assert_eq!(account_state.amount, 700);
+    assert_eq!(account_state.delegate, Some(delegate));
+    assert_eq!(account_state.delegated_amount, 300);
+    assert_eq!(account_state.state, AccountState::Frozen);
+    assert_eq!(account_state.is_native, None);
+    assert_eq!(account_state.close_authority, None);
+
+    // Token-2022 extensions
+    let close_authority = Pubkey::new_unique();
+    let mint_2022 = MintBuilder::new(&mint_authority, 6)
+        .transfer_fee(25, 10_000)
+        .close_authority(close_authority)
+        .metadata("Kite", "KITE", "https://example.com/kite.json")
+        .create(&mut litesvm)
+        .unwrap();
+    let mint_state = get_mint(&litesvm, &mint_2022).unwrap();
+    assert_eq!(mint_state.token_program, spl_token_2022::ID);
+    assert_eq!(mint_state.close_authority, Some(close_authority));
+    let transfer_fee_config = mint_state.extension::<TransferFeeConfig>().unwrap();
+    assert_eq!(u64::from(transfer_fee_config.newer_transfer_fee.maximum_fee), 10_000);
+    assert_eq!(mint_state.token_metadata().unwrap().name, "Kite");
+
+    let token_account_2022 =
+        create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint_2022, &owner, &spl_token_2022::ID).unwrap();
+    let account_state = get_token_account(&litesvm, &token_account_2022).unwrap();
+    assert_eq!(account_state.token_program, spl_token_2022::ID);
+    assert!(account_state.extension_types.contains(&ExtensionType::TransferFeeAmount));
+    assert_eq!(account_state.withheld_amount(), 0);
+
+    // Accounts of other programs, or of the wrong type, are rejected
+    let error = get_mint(&litesvm, &owner.pubkey()).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+    let error = get_token_account(&litesvm, &mint).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+}
+
+#[test]
+fn test_set_token_balance_and_mint_state() {
+    use spl_token_2022::state::{AccountState, Mint};
+
+    let mut litesvm = LiteSVM::new();
+    let alice = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let bob = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+    // A mint nobody holds the authority keypair for
+    let usdc = Pubkey::new_unique();
+    let mint_state = Mint { decimals: 6, is_initialized: true, ..Default::default() };
+    set_mint_state(&mut litesvm, &usdc, &mint_state, &spl_token::ID).unwrap();
+
+    let alice_account = set_token_balance(&mut litesvm, &alice.pubkey(), &usdc, 1_000_000).unwrap();
+    assert_eq!(alice_account, spl_associated_token_account::get_associated_token_address(&alice.pubkey(), &usdc));
+    assert_token_balance(&litesvm, &alice_account, 1_000_000, "Alice should have 1 USDC");
+    let bob_account = set_token_balance(&mut litesvm, &bob.pubkey(), &usdc, 500).unwrap();
+    assert_eq!(get_mint(&litesvm, &usdc).unwrap().supply, 1_000_500);
+
+    // Overwriting a balance adjusts the supply, and the injected accounts work with the token program
+    set_token_balance(&mut litesvm, &alice.pubkey(), &usdc, 2_000).unwrap();
+    assert_eq!(get_mint(&litesvm, &usdc).unwrap().supply, 2_500);
+    transfer_tokens_checked(&mut litesvm, &usdc, &alice_account, &bob_account, 1_500, &alice, &spl_token::ID).unwrap();
+    assert_token_balance(&litesvm, &bob_account, 2_000, "Bob should have received tokens");
+
+    // Changing the mint's state keeps its supply in sync with what's written
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mut mint_state = get_mint(&litesvm, &usdc).unwrap().to_mint_state();
+    mint_state.mint_authority = Some(mint_authority.pubkey()).into();
+    set_mint_state(&mut litesvm, &usdc, &mint_state, &spl_token::ID).unwrap();
+    mint_tokens_to_account(&mut litesvm, &usdc, &bob_account, 1, &mint_authority, &spl_token::ID).unwrap();
+    assert_eq!(get_mint(&litesvm, &usdc).unwrap().supply, 2_501);
+    assert!(set_mint_state(&mut litesvm, &usdc, &mint_state, &spl_token_2022::ID).is_err());
+
+    // Accounts that aren't token accounts are never replaced
+    let carol = Pubkey::new_unique();
+    let carol_account = spl_associated_token_account::get_associated_token_address(&carol, &usdc);
+    litesvm.airdrop(&carol_account, 1_000_000).unwrap();
+    let result = set_token_balance(&mut litesvm, &carol, &usdc, 1);
+    assert!(matches!(result, Err(SolanaKiteError::TokenOperationFailed(_))));
+    assert_eq!(litesvm.get_balance(&carol_account).unwrap(), 1_000_000);
+
+    // A supply lower than an account's balance is reported, not hidden
+    let mut inconsistent_state = get_mint(&litesvm, &usdc).unwrap().to_mint_state();
+    inconsistent_state.supply = 0;
+    set_mint_state(&mut litesvm, &usdc, &inconsistent_state, &spl_token::ID).unwrap();
+    let result = set_token_balance(&mut litesvm, &alice.pubkey(), &usdc, 1);
+    assert!(matches!(result, Err(SolanaKiteError::TokenOperationFailed(_))));
+    assert_token_balance(&litesvm, &alice_account, 500, "Alice's balance should be unchanged");
+
+    // Token-2022 accounts get the extensions their mint needs
+    let mint_2022 = MintBuilder::new(&mint_authority, 6)
+        .transfer_fee(100, 1_000)
+        .freeze_authority(mint_authority.pubkey())
+        .default_account_state(AccountState::Frozen)
+        .create(&mut litesvm)
+        .unwrap();
+    let alice_account = set_token_balance(&mut litesvm, &alice.pubkey(), &mint_2022, 10_000).unwrap();
+    let bob_account = create_associated_token_account(&mut litesvm, &bob.pubkey(), &mint_2022, &bob, &spl_token_2022::ID).unwrap();
+    assert_eq!(
+        litesvm.get_account(&alice_account).unwrap().data.len(),
+        litesvm.get_account(&bob_account).unwrap().data.len()
+    );
+    assert!(get_token_account(&litesvm, &alice_account).unwrap().is_frozen());
+    thaw_token_account(&mut litesvm, &mint_2022, &alice_account, &mint_authority, &spl_token_2022::ID).unwrap();
+    thaw_token_account(&mut litesvm, &mint_2022, &bob_account, &mint_authority, &spl_token_2022::ID).unwrap();
+    let withheld =
+        transfer_tokens_checked(&mut litesvm, &mint_2022, &alice_account, &bob_account, 10_000, &alice, &spl_token_2022::ID).unwrap();
+    assert_eq!(withheld, 100);
+}
+
+#[test]
+fn test_wrap_and_unwrap_sol() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut litesvm = LiteSVM::new();
+        let owner = create_wallet(&mut litesvm, 5_000_000_000).unwrap();
+
+        let wsol_account = wrap_sol(&mut litesvm, &owner, 1_000_000_000, &token_program).unwrap();
+        // Wrapping again tops up the same account
+        assert_eq!(wrap_sol(&mut litesvm, &owner, 500_000_000, &token_program).unwrap(), wsol_account);
+
+        let account_state = get_token_account(&litesvm, &wsol_account).unwrap();
+        assert_eq!(account_state.amount, 1_500_000_000);
+        assert!(account_state.is_native.is_some());
+
+        // Unwrapping closes the account, so simulate the close to learn its fee
+        let close_instruction =
+            spl_token_2022::instruction::close_account(&token_program, &wsol_account, &owner.pubkey(), &owner.pubkey(), &[])
+                .unwrap();
+        let fee = simulate_transaction_from_instructions(&litesvm, vec![close_instruction], &[&owner], &owner.pubkey())
+            .unwrap()
+            .result
+            .fee;
+
+        let balance_before = litesvm.get_balance(&owner.pubkey()).unwrap();
+        let rent = litesvm.get_balance(&wsol_account).unwrap() - 1_500_000_000;
+        assert_eq!(unwrap_sol(&mut litesvm, &owner, &token_program).unwrap(), 1_500_000_000);
+        check_account_is_closed(&litesvm, &wsol_account, "Wrapped SOL account should be closed");
+        assert_eq!(litesvm.get_balance(&owner.pubkey()).unwrap(), balance_before + 1_500_000_000 + rent - fee);
+    }
+
+    // Injected wrapped SOL balances are backed by lamports
+    let mut litesvm = LiteSVM::new();
+    let owner = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let wsol_account = set_token_balance(&mut litesvm, &owner.pubkey(), &spl_token::native_mint::ID, 3_000_000_000).unwrap();
+    assert_eq!(unwrap_sol(&mut litesvm, &owner, &spl_token::ID).unwrap(), 3_000_000_000);
+    check_account_is_closed(&litesvm, &wsol_account, "Wrapped SOL account should be closed");
+}
+
+#[test]
+fn test_multisig_authorities() {
+    use spl_token_2022::instruction::AuthorityType;
+    use spl_token_2022::state::AccountState;
+
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut litesvm = LiteSVM::new();
+        let signers = create_wallets(&mut litesvm, 3, 1_000_000_000).unwrap();
+        let signer_pubkeys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+        let multisig = create_multisig(&mut litesvm, &signer_pubkeys, 2, &token_program).unwrap();
+        let recipient = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+        // A 2 of 3 multisig holds the mint authority and owns the treasury account
+        let mint = create_token_mint(&mut litesvm, &signers[0], 6, None, Some(multisig), &token_program).unwrap();
+        set_token_authority(&mut litesvm, &mint, AuthorityType::MintTokens, Some(&multisig), &signers[0], &token_program).unwrap();
+        let treasury = create_associated_token_account(&mut litesvm, &multisig, &mint, &signers[0], &token_program).unwrap();
+        let recipient_account =
+            create_associated_token_account(&mut litesvm, &recipient.pubkey(), &mint, &recipient, &token_program).unwrap();
+
+        let two_signers = [&signers[0], &signers[2]];
+        mint_tokens_to_account(&mut litesvm, &mint, &treasury, 1_000, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        let one_signer = [&signers[1]];
+        let error =
+            mint_tokens_to_account(&mut litesvm, &mint, &treasury, 1, TokenAuthority::multisig(&multisig, &one_signer), &token_program)
+                .unwrap_err();
+        assert!(error.transaction_failure().is_some());
+
+        let two_signers = [&signers[1], &signers[2]];
+        transfer_tokens(&mut litesvm, &treasury, &recipient_account, 100, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        transfer_tokens_checked(
+            &mut litesvm,
+            &mint,
+            &treasury,
+            &recipient_account,
+            200,
+            TokenAuthority::multisig(&multisig, &two_signers),
+            &token_program,
+        ).unwrap();
+        burn_tokens(&mut litesvm, &mint, &treasury, 300, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+
+        assert_token_balance(&litesvm, &treasury, 400, "Treasury should keep the rest");
+        assert_token_balance(&litesvm, &recipient_account, 300, "Recipient should receive both transfers");
+
+        // The multisig can also delegate, freeze, close and hand over its authorities
+        let delegate = Pubkey::new_unique();
+        approve_delegate(&mut litesvm, &treasury, &delegate, 10, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        approve_delegate_checked(
+            &mut litesvm,
+            &mint,
+            &treasury,
+            &delegate,
+            20,
+            TokenAuthority::multisig(&multisig, &two_signers),
+            &token_program,
+        ).unwrap();
+        assert_eq!(get_token_account(&litesvm, &treasury).unwrap().delegated_amount, 20);
+        revoke_delegate(&mut litesvm, &treasury, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        assert_eq!(get_token_account(&litesvm, &treasury).unwrap().delegate, None);
+
+        freeze_token_account(&mut litesvm, &mint, &recipient_account, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        assert_eq!(get_token_account(&litesvm, &recipient_account).unwrap().state, AccountState::Frozen);
+        thaw_token_account(&mut litesvm, &mint, &recipient_account, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        assert_eq!(get_token_account(&litesvm, &recipient_account).unwrap().state, AccountState::Initialized);
+
+        burn_tokens(&mut litesvm, &mint, &treasury, 400, TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        close_token_account(&mut litesvm, &treasury, &recipient.pubkey(), TokenAuthority::multisig(&multisig, &two_signers), &token_program).unwrap();
+        check_account_is_closed(&litesvm, &treasury, "The multisig's treasury should be closed");
+
+        set_token_authority(
+            &mut litesvm,
+            &mint,
+            AuthorityType::MintTokens,
+            None,
+            TokenAuthority::multisig(&multisig, &two_signers),
+            &token_program,
+        ).unwrap();
+        assert_eq!(get_mint(&litesvm, &mint).unwrap().mint_authority, None);
+
+        // Without signers, nothing can sign or pay for the transaction
+        let error =
+            freeze_token_account(&mut litesvm, &mint, &recipient_account, TokenAuthority::multisig(&multisig, &[]), &token_program)
+                .unwrap_err();
+        assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+        let error = transfer_tokens(&mut litesvm, &recipient_account, &recipient_account, 1, TokenAuthority::multisig(&multisig, &[]), &token_program)
+            .unwrap_err();
+        assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+    }
+
+    let mut litesvm = LiteSVM::new();
+    let error = create_multisig(&mut litesvm, &[Pubkey::new_unique()], 2, &spl_token::ID).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+}
+
+#[test]
+fn test_transfer_with_transfer_fee() {
+    let mut litesvm = LiteSVM::new();
+    let token_program = spl_token_2022::ID;
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let alice = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let bob = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+    // 2% fee, capped at 3,000 base units
+    let mint = MintBuilder::new(&mint_authority, 6).transfer_fee(200, 3_000).create(&mut litesvm).unwrap();
+    let alice_account = create_associated_token_account(&mut litesvm, &alice.pubkey(), &mint, &alice, &token_program).unwrap();
+    let bob_account = create_associated_token_account(&mut litesvm, &bob.pubkey(), &mint, &bob, &token_program).unwrap();
+    mint_tokens_to_account(&mut litesvm, &mint, &alice_account, 1_000_000, &mint_authority, &token_program).unwrap();
+
+    let withheld =
+        transfer_tokens_checked(&mut litesvm, &mint, &alice_account, &bob_account, 100_000, &alice, &token_program).unwrap();
+    assert_eq!(withheld, 2_000);
+    assert_token_balance(&litesvm, &bob_account, 98_000, "Bob should receive the amount minus the fee");
+
+    let withheld =
+        transfer_tokens_checked(&mut litesvm, &mint, &alice_account, &bob_account, 500_000, &alice, &token_program).unwrap();
+    assert_eq!(withheld, 3_000);
+    assert_token_balance(&litesvm, &alice_account, 400_000, "Alice pays the full amount");
+}
+
+#[test]
+fn test_mint_builder_with_extensions() {
+    use spl_token_2022::extension::{
+        default_account_state::DefaultAccountState, transfer_fee::TransferFeeConfig, BaseStateWithExtensions,
+        ExtensionType, StateWithExtensions,
+    };
+    use spl_token_2022::state::{AccountState, Mint};
+    use spl_token_metadata_interface::state::TokenMetadata;
+
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let delegate = Pubkey::new_unique();
+    let hook_program = Pubkey::new_unique();
+
+    let builder = MintBuilder::new(&mint_authority, 6)
+        .freeze_authority(mint_authority.pubkey())
+        .transfer_fee(100, 1_000)
+        .interest_bearing(500)
+        .non_transferable()
+        .permanent_delegate(delegate)
+        .close_authority(mint_authority.pubkey())
+        .default_account_state(AccountState::Frozen)
+        .metadata("Kite", "KITE", "https://example.com/kite.json")
+        .transfer_hook(hook_program);
+    let expected_size = builder.account_size().unwrap();
+    let mint = builder.create(&mut litesvm).unwrap();
+
+    let account = litesvm.get_account(&mint).unwrap();
+    assert_eq!(account.owner, spl_token_2022::ID);
+    assert_eq!(account.data.len(), expected_size);
+
+    let state = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    assert_eq!(state.base.decimals, 6);
+    let mut extension_types = state.get_extension_types().unwrap();
+    extension_types.sort_by_key(|extension_type| *extension_type as u16);
+    assert_eq!(
+        extension_types,
+        vec![
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::DefaultAccountState,
+            ExtensionType::NonTransferable,
+            ExtensionType::InterestBearingConfig,
+            ExtensionType::PermanentDelegate,
+            ExtensionType::TransferHook,
+            ExtensionType::MetadataPointer,
+            ExtensionType::TokenMetadata,
+        ]
+    );
+    let transfer_fee = state.get_extension::<TransferFeeConfig>().unwrap();
+    assert_eq!(u16::from(transfer_fee.newer_transfer_fee.transfer_fee_basis_points), 100);
+    let default_state = state.get_extension::<DefaultAccountState>().unwrap();
+    assert_eq!(default_state.state, AccountState::Frozen as u8);
+    let metadata = state.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.symbol, "KITE");
+    assert_eq!(metadata.mint, mint);
+
+    // Extensions need Token-2022
+    let error = MintBuilder::new(&mint_authority, 6)
+        .token_program(&spl_token::ID)
+        .non_transferable()
+        .create(&mut litesvm)
+        .unwrap_err();
+    assert!(matches!(error, SolanaKiteError::TokenOperationFailed(_)));
+}
+
 #[test]
 fn test_transaction_sending() {
     let mut litesvm = LiteSVM::new();
@@ -86,6 +625,527 @@ fn test_transaction_sending() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_transaction_result_details() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+
+    // Creating an ATA CPIs into the System and Token programs
+    let instruction = spl_associated_token_account::instruction::create_associated_token_account(
+        &user.pubkey(),
+        &user.pubkey(),
+        &mint,
+        &spl_token::id(),
+    );
+    let result = send_transaction_from_instructions(
+        &mut litesvm,
+        vec![instruction],
+        &[&user],
+        &user.pubkey(),
+    ).unwrap();
+
+    assert!(result.compute_units_consumed > 0);
+    assert_eq!(result.fee, 5_000);
+    assert!(!result.logs.is_empty());
+    assert!(!result.inner_instructions[0].is_empty());
+    let ata_rent = litesvm.minimum_balance_for_rent_exemption(165);
+    let balance = litesvm.get_balance(&user.pubkey()).unwrap();
+    assert_eq!(balance, 1_000_000_000 - ata_rent - result.fee);
+    assert!(litesvm.get_transaction(&result.signature).is_some());
+
+    // The ATA program CPIs into the Token program to size, lock and initialize the account
+    let invocations = result.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].program_id, spl_associated_token_account::id());
+    assert_eq!(invocations[0].outcome, InvocationOutcome::Success);
+    assert_eq!(invocations[0].inner_invocations_of(&spl_token::id()).len(), 3);
+    assert!(result.call_trace().contains("[2] TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"));
+
+    // Signatures verified by precompiles are charged like transaction signatures
+    let message = b"kite";
+    let signature = user.sign_message(message);
+    let ed25519_instruction = solana_ed25519_program::new_ed25519_instruction_with_signature(
+        message,
+        &signature.into(),
+        &user.pubkey().to_bytes(),
+    );
+    let balance_before = litesvm.get_balance(&user.pubkey()).unwrap();
+    let result = send_transaction_from_instructions(
+        &mut litesvm,
+        vec![ed25519_instruction],
+        &[&user],
+        &user.pubkey(),
+    ).unwrap();
+    assert_eq!(result.fee, 10_000);
+    assert_eq!(litesvm.get_balance(&user.pubkey()).unwrap(), balance_before - result.fee);
+}
+
+#[test]
+fn test_transaction_fee_follows_feature_set() {
+    use agave_feature_set::{enable_secp256r1_precompile, FeatureSet};
+
+    // Without the secp256r1 precompile, its address is just another program
+    let mut feature_set = FeatureSet::all_enabled();
+    feature_set.deactivate(&enable_secp256r1_precompile::id());
+    let mut litesvm = LiteSVM::default()
+        .with_feature_set(feature_set)
+        .with_builtins()
+        .with_lamports(1_000_000_000_000_000)
+        .with_sysvars()
+        .with_precompiles()
+        .with_default_programs()
+        .with_sigverify(true)
+        .with_blockhash_check(true);
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+    // The memo program accepts the data, which would claim one secp256r1 signature
+    let memo_program: Pubkey = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse().unwrap();
+    let memo_binary = litesvm.get_account(&memo_program).unwrap().data;
+    let secp256r1_program = solana_sdk_ids::secp256r1_program::id();
+    deploy_program_from_bytes(&mut litesvm, &secp256r1_program, &memo_binary).unwrap();
+    let instruction = solana_instruction::Instruction::new_with_bytes(secp256r1_program, &[1, 0], vec![]);
+
+    let balance_before = litesvm.get_balance(&user.pubkey()).unwrap();
+    let result = send_transaction_from_instructions(&mut litesvm, vec![instruction], &[&user], &user.pubkey()).unwrap();
+    assert_eq!(result.fee, 5_000);
+    assert_eq!(litesvm.get_balance(&user.pubkey()).unwrap(), balance_before - result.fee);
+}
+
+#[test]
+fn test_transaction_failure_details() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let recipient = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let user_account = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint, &user, &spl_token::ID).unwrap();
+    let recipient_account = create_associated_token_account(&mut litesvm, &recipient.pubkey(), &mint, &recipient, &spl_token::ID).unwrap();
+
+    // Transferring from an empty account fails with TokenError::InsufficientFunds (1)
+    let transfer_instruction = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &user_account,
+        &recipient_account,
+        &user.pubkey(),
+        &[],
+        1,
+    ).unwrap();
+    let error = send_transaction_from_instructions(
+        &mut litesvm,
+        vec![transfer_instruction],
+        &[&user],
+        &user.pubkey(),
+    ).unwrap_err();
+
+    match error {
+        SolanaKiteError::TransactionExecutionFailed(failure) => {
+            assert_eq!(failure.instruction_index, Some(0));
+            assert_eq!(failure.custom_error_code, Some(1));
+            assert!(failure.logs.iter().any(|log| log.contains("insufficient funds")));
+            assert!(failure.call_trace().contains("failed: custom program error: 0x1"));
+        }
+        other => panic!("Expected TransactionExecutionFailed, got {:?}", other),
+    }
+
+    // Token helpers report the same structured failure, here TokenError::OwnerMismatch (4)
+    let error = mint_tokens_to_account(&mut litesvm, &mint, &user_account, 1, &user, &spl_token::ID).unwrap_err();
+    match error {
+        SolanaKiteError::TransactionExecutionFailed(failure) => {
+            assert_eq!(failure.custom_error_code, Some(4));
+        }
+        other => panic!("Expected TransactionExecutionFailed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_transaction_builder() {
+    let mut litesvm = LiteSVM::new();
+    let payer = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let bystander = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let token_account = create_associated_token_account(&mut litesvm, &payer.pubkey(), &mint, &payer, &spl_token::ID).unwrap();
+
+    let mint_to_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &token_account,
+        &mint_authority.pubkey(),
+        &[],
+        100,
+    ).unwrap();
+
+    // Without the mint authority the builder reports it as missing, before sending
+    let builder = TransactionBuilder::new(&payer).instruction(mint_to_instruction.clone());
+    assert_eq!(builder.required_signers(), vec![payer.pubkey(), mint_authority.pubkey()]);
+    match builder.send(&mut litesvm).unwrap_err() {
+        SolanaKiteError::MissingSigners(missing) => assert_eq!(missing, vec![mint_authority.pubkey()]),
+        other => panic!("Expected MissingSigners, got {:?}", other),
+    }
+
+    // Duplicate and unneeded signers are fine, only the fee payer and mint authority sign
+    let result = TransactionBuilder::new(&payer)
+        .instructions(vec![mint_to_instruction.clone(), mint_to_instruction])
+        .signers([&mint_authority as &dyn Signer, &bystander, &mint_authority])
+        .compute_budget(ComputeBudgetOptions {
+            compute_unit_limit: Some(100_000),
+            ..Default::default()
+        })
+        .send(&mut litesvm)
+        .unwrap();
+
+    // Two signatures: the fee payer and the mint authority
+    assert_eq!(result.fee, 10_000);
+    assert_eq!(result.instruction_compute_units().unwrap().len(), 3);
+    assert_token_balance(&litesvm, &token_account, 200, "Both mints should succeed");
+}
+
+#[test]
+fn test_versioned_transaction_with_lookup_table() {
+    let mut litesvm = LiteSVM::new();
+    let payer = create_wallet(&mut litesvm, 10_000_000_000).unwrap();
+    let recipients: Vec<Pubkey> = (0..30).map(|_| Pubkey::new_unique()).collect();
+
+    let lookup_table = create_lookup_table(&mut litesvm, &payer.pubkey(), &payer).unwrap();
+
+    // Extending moves the clock on a slot, unless there's nothing to add
+    let slot = litesvm.get_sysvar::<solana_program::clock::Clock>().slot;
+    extend_lookup_table(&mut litesvm, &lookup_table, &payer, vec![]).unwrap();
+    assert_eq!(litesvm.get_sysvar::<solana_program::clock::Clock>().slot, slot);
+    extend_lookup_table(&mut litesvm, &lookup_table, &payer, recipients.clone()).unwrap();
+    assert_eq!(litesvm.get_sysvar::<solana_program::clock::Clock>().slot, slot + 1);
+    freeze_lookup_table(&mut litesvm, &lookup_table, &payer).unwrap();
+    assert_eq!(get_lookup_table_addresses(&litesvm, &lookup_table).unwrap(), recipients);
+
+    // Accounts owned by other programs aren't read as lookup tables
+    let mut impostor = litesvm.get_account(&lookup_table).unwrap();
+    impostor.owner = solana_program::system_program::id();
+    let impostor_address = Pubkey::new_unique();
+    litesvm.set_account(impostor_address, impostor).unwrap();
+    let error = get_lookup_table_addresses(&litesvm, &impostor_address).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::AccountOperationFailed(_)));
+
+    // 30 recipients are too many accounts for a legacy transaction
+    let transfer_instructions: Vec<_> = recipients
+        .iter()
+        .map(|recipient| solana_program::system_instruction::transfer(&payer.pubkey(), recipient, 1_000_000))
+        .collect();
+    let result = send_versioned_transaction_from_instructions(
+        &mut litesvm,
+        transfer_instructions,
+        &[&payer],
+        &payer.pubkey(),
+        &[lookup_table],
+    ).unwrap();
+    assert_eq!(result.fee, 5_000);
+
+    for recipient in &recipients {
+        assert_eq!(litesvm.get_balance(recipient).unwrap(), 1_000_000);
+    }
+
+    // Missing signers are reported by pubkey, and other signing problems as build failures
+    let sender = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let transfer_instruction = solana_program::system_instruction::transfer(&sender.pubkey(), &recipients[0], 1);
+    let error = send_versioned_transaction_from_instructions(
+        &mut litesvm,
+        vec![transfer_instruction.clone()],
+        &[&payer],
+        &payer.pubkey(),
+        &[lookup_table],
+    ).unwrap_err();
+    match error {
+        SolanaKiteError::MissingSigners(missing) => assert_eq!(missing, vec![sender.pubkey()]),
+        other => panic!("Expected MissingSigners, got {:?}", other),
+    }
+    let bystander = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let error = send_versioned_transaction_from_instructions(
+        &mut litesvm,
+        vec![transfer_instruction],
+        &[&payer, &sender, &bystander],
+        &payer.pubkey(),
+        &[lookup_table],
+    ).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::TransactionBuildFailed(_)));
+
+    // Frozen tables can't be extended, and the authority can make a new table in the next slot
+    assert!(extend_lookup_table(&mut litesvm, &lookup_table, &payer, vec![Pubkey::new_unique()]).is_err());
+    let second_lookup_table = create_lookup_table(&mut litesvm, &payer.pubkey(), &payer).unwrap();
+    assert_ne!(second_lookup_table, lookup_table);
+    deactivate_lookup_table(&mut litesvm, &second_lookup_table, &payer).unwrap();
+    assert!(extend_lookup_table(&mut litesvm, &second_lookup_table, &payer, vec![Pubkey::new_unique()]).is_err());
+}
+
+#[test]
+fn test_transaction_simulation() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let token_account = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint, &user, &spl_token::ID).unwrap();
+
+    let mint_to_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &token_account,
+        &mint_authority.pubkey(),
+        &[],
+        500,
+    ).unwrap();
+
+    let simulation = simulate_transaction_from_instructions(
+        &litesvm,
+        vec![mint_to_instruction.clone()],
+        &[&mint_authority],
+        &mint_authority.pubkey(),
+    ).unwrap();
+
+    // The simulated post-state has the new balance, but LiteSVM is unchanged
+    let post_account = simulation.post_account(&token_account).unwrap();
+    assert_eq!(u64::from_le_bytes(post_account.data[64..72].try_into().unwrap()), 500);
+    assert_token_balance(&litesvm, &token_account, 0, "Simulation should not commit");
+    assert!(simulation.result.compute_units_consumed > 0);
+
+    let result = send_transaction_from_instructions(
+        &mut litesvm,
+        vec![mint_to_instruction],
+        &[&mint_authority],
+        &mint_authority.pubkey(),
+    ).unwrap();
+    assert_eq!(result.logs, simulation.result.logs);
+    assert_token_balance(&litesvm, &token_account, 500, "Sent transaction should commit");
+
+    // Failures are reported the same way as when sending
+    let bad_mint_to_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &token_account,
+        &user.pubkey(),
+        &[],
+        500,
+    ).unwrap();
+    let error = simulate_transaction_from_instructions(
+        &litesvm,
+        vec![bad_mint_to_instruction],
+        &[&user],
+        &user.pubkey(),
+    ).unwrap_err();
+    assert_eq!(error.transaction_failure().unwrap().custom_error_code, Some(4));
+}
+
+#[test]
+fn test_compute_budget() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let token_account = create_associated_token_account(
+        &mut litesvm,
+        &mint_authority.pubkey(),
+        &mint,
+        &mint_authority,
+        &spl_token::ID,
+    ).unwrap();
+
+    let mint_to_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &token_account,
+        &mint_authority.pubkey(),
+        &[],
+        1_000,
+    ).unwrap();
+
+    let result = send_transaction_with_compute_budget(
+        &mut litesvm,
+        vec![mint_to_instruction.clone()],
+        &[&mint_authority],
+        &mint_authority.pubkey(),
+        &ComputeBudgetOptions {
+            compute_unit_limit: Some(50_000),
+            compute_unit_price: Some(10),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    // Two compute budget instructions come before the mint
+    let compute_units = result.instruction_compute_units().unwrap();
+    assert_eq!(compute_units.len(), 3);
+    assert!(compute_units[2] > 0);
+    assert_instruction_compute_units(&result, 2, 50_000);
+
+    // A limit lower than the mint needs makes the mint instruction fail
+    let error = send_transaction_with_compute_budget(
+        &mut litesvm,
+        vec![mint_to_instruction],
+        &[&mint_authority],
+        &mint_authority.pubkey(),
+        &ComputeBudgetOptions {
+            compute_unit_limit: Some(1_000),
+            ..Default::default()
+        },
+    ).unwrap_err();
+    assert_eq!(error.transaction_failure().unwrap().instruction_index, Some(1));
+    assert_token_balance(&litesvm, &token_account, 1_000, "Only the first mint should succeed");
+}
+
+#[test]
+#[should_panic(expected = "more than the maximum of 10")]
+fn test_compute_unit_assertion_reports_regression() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let result = send_transaction_from_instructions(
+        &mut litesvm,
+        vec![spl_token::instruction::amount_to_ui_amount(&spl_token::id(), &mint, 1).unwrap()],
+        &[&mint_authority],
+        &mint_authority.pubkey(),
+    ).unwrap();
+
+    assert_instruction_compute_units(&result, 0, 10);
+}
+
+#[test]
+fn test_compute_units_require_complete_logs() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let result = send_transaction_from_instructions(
+        &mut litesvm,
+        vec![spl_token::instruction::amount_to_ui_amount(&spl_token::id(), &mint, 1).unwrap()],
+        &[&mint_authority],
+        &mint_authority.pubkey(),
+    ).unwrap();
+    assert_eq!(result.instruction_compute_units().unwrap().len(), 1);
+
+    // Truncated logs can't be matched to instructions
+    let mut truncated = result.clone();
+    truncated.logs.truncate(1);
+    truncated.logs.push("Log truncated".to_string());
+    let error = truncated.instruction_compute_units().unwrap_err();
+    assert!(matches!(error, SolanaKiteError::DeserializationFailed(_)));
+
+    // Neither can logs missing an instruction, like a precompile that isn't logged
+    let mut unlogged_instruction = result.clone();
+    unlogged_instruction.inner_instructions.insert(0, vec![]);
+    let error = unlogged_instruction.instruction_compute_units().unwrap_err();
+    assert!(matches!(error, SolanaKiteError::DeserializationFailed(_)));
+}
+
+#[test]
+fn test_view_instruction_return_data() {
+    let mut litesvm = LiteSVM::new();
+    let payer = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &payer, 6, None, None, &spl_token::ID).unwrap();
+
+    // GetAccountDataSize returns the token account size as a little-endian u64
+    let instruction = spl_token::instruction::get_account_data_size(&spl_token::id(), &mint).unwrap();
+    let size: u64 = call_view_instruction(&litesvm, instruction.clone(), &[&payer], &payer.pubkey()).unwrap();
+    assert_eq!(size, 165);
+
+    // The wrong type is reported rather than silently misread
+    let error = call_view_instruction::<u32>(&litesvm, instruction, &[&payer], &payer.pubkey()).unwrap_err();
+    assert!(matches!(error, SolanaKiteError::DeserializationFailed(_)));
+
+    // Return data that isn't Borsh-encoded, like AmountToUiAmount's raw UTF-8 string, is reported too
+    let instruction = spl_token::instruction::amount_to_ui_amount(&spl_token::id(), &mint, 1).unwrap();
+    let ui_amount: Result<String, _> = call_view_instruction(&litesvm, instruction, &[&payer], &payer.pubkey());
+    assert!(ui_amount.is_err());
+}
+
+#[test]
+fn test_expected_failure_assertions() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let token_account = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint, &user, &spl_token::ID).unwrap();
+
+    let mint_to_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &token_account,
+        &user.pubkey(),
+        &[],
+        1,
+    ).unwrap();
+
+    // TokenError::OwnerMismatch (4)
+    assert_transaction_fails_with_custom_error(
+        &mut litesvm,
+        vec![mint_to_instruction.clone()],
+        &[&user],
+        &user.pubkey(),
+        4,
+    );
+
+    // The read-only instruction succeeds, so the failing instruction is the second one
+    let read_only_instruction = spl_token::instruction::amount_to_ui_amount(&spl_token::id(), &mint, 1).unwrap();
+    assert_instruction_error(
+        &mut litesvm,
+        vec![read_only_instruction, mint_to_instruction],
+        &[&user],
+        &user.pubkey(),
+        1,
+        InstructionError::Custom(4),
+    );
+}
+
+#[test]
+#[should_panic(expected = "expected: custom program error: 0x5 (5)")]
+fn test_expected_failure_assertion_reports_mismatch() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let token_account = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint, &user, &spl_token::ID).unwrap();
+
+    let mint_to_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &token_account,
+        &user.pubkey(),
+        &[],
+        1,
+    ).unwrap();
+
+    assert_transaction_fails_with_custom_error(
+        &mut litesvm,
+        vec![mint_to_instruction],
+        &[&user],
+        &user.pubkey(),
+        5,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Logs:\n  Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]")]
+fn test_expected_failure_assertion_reports_raw_logs() {
+    let mut litesvm = LiteSVM::new();
+    let mint_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let token_account = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint, &user, &spl_token::ID).unwrap();
+
+    let mint_to_instruction = spl_token::instruction::mint_to(
+        &spl_token::id(),
+        &mint,
+        &token_account,
+        &user.pubkey(),
+        &[],
+        1,
+    ).unwrap();
+
+    // The call trace only shows lines inside program invocations, so the raw logs follow it
+    assert_transaction_fails_with_custom_error(
+        &mut litesvm,
+        vec![mint_to_instruction],
+        &[&user],
+        &user.pubkey(),
+        5,
+    );
+}
+
 #[test]
 fn test_pda_generation() {
     let program_id = Pubkey::new_unique();
@@ -145,8 +1205,8 @@ fn test_multiple_token_mints() {
     // Create multiple token mints with different decimals
     // Use a specific mint address for the 6-decimal token
     let specified_mint = Pubkey::new_unique();
-    let mint_6_decimals = create_token_mint(&mut litesvm, &mint_authority, 6, Some(specified_mint)).unwrap();
-    let mint_9_decimals = create_token_mint(&mut litesvm, &mint_authority, 9, None).unwrap();
+    let mint_6_decimals = create_token_mint(&mut litesvm, &mint_authority, 6, Some(specified_mint), None, &spl_token::ID).unwrap();
+    let mint_9_decimals = create_token_mint(&mut litesvm, &mint_authority, 9, None, None, &spl_token::ID).unwrap();
     
     // Verify that the 6-decimal mint uses our specified address
     assert_eq!(mint_6_decimals, specified_mint, "6-decimal mint should use specified address");
@@ -157,6 +1217,7 @@ fn test_multiple_token_mints() {
         &user.pubkey(),
         &mint_6_decimals,
         &user,
+        &spl_token::ID,
     ).unwrap();
     
     let account_9 = create_associated_token_account(
@@ -164,6 +1225,7 @@ fn test_multiple_token_mints() {
         &user.pubkey(),
         &mint_9_decimals,
         &user,
+        &spl_token::ID,
     ).unwrap();
     
     // Mint different amounts to each account
@@ -173,6 +1235,7 @@ fn test_multiple_token_mints() {
         &account_6,
         1_000_000, // 1 token with 6 decimals
         &mint_authority,
+        &spl_token::ID,
     ).unwrap();
     
     mint_tokens_to_account(
@@ -181,9 +1244,334 @@ fn test_multiple_token_mints() {
         &account_9,
         1_000_000_000, // 1 token with 9 decimals
         &mint_authority,
+        &spl_token::ID,
     ).unwrap();
     
     // Verify balances
     assert_token_balance(&litesvm, &account_6, 1_000_000, "6-decimal token balance");
     assert_token_balance(&litesvm, &account_9, 1_000_000_000, "9-decimal token balance");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_token_ui_amounts() {
+    let mut litesvm = LiteSVM::new();
+
+    let mint_authority = create_wallet(&mut litesvm, 2_000_000_000).unwrap();
+    let user = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint_6_decimals = create_token_mint(&mut litesvm, &mint_authority, 6, None, None, &spl_token::ID).unwrap();
+    let mint_9_decimals = create_token_mint(&mut litesvm, &mint_authority, 9, None, None, &spl_token::ID).unwrap();
+    let account_6 = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint_6_decimals, &user, &spl_token::ID).unwrap();
+    let account_9 = create_associated_token_account(&mut litesvm, &user.pubkey(), &mint_9_decimals, &user, &spl_token::ID).unwrap();
+
+    // Human amounts become base units using each mint's decimals
+    let amount_6 = from_ui_amount(&litesvm, &mint_6_decimals, "1").unwrap();
+    let amount_9 = from_ui_amount(&litesvm, &mint_9_decimals, "1.5").unwrap();
+    assert_eq!(amount_6, 1_000_000);
+    assert_eq!(amount_9, 1_500_000_000);
+    mint_tokens_to_account(&mut litesvm, &mint_6_decimals, &account_6, amount_6, &mint_authority, &spl_token::ID).unwrap();
+    mint_tokens_to_account(&mut litesvm, &mint_9_decimals, &account_9, amount_9, &mint_authority, &spl_token::ID).unwrap();
+    assert_token_ui_balance(&litesvm, &account_6, "1");
+    assert_token_ui_balance(&litesvm, &account_9, "1.5");
+
+    // The same base units are a different human amount for each mint
+    assert_eq!(to_ui_amount(&litesvm, &mint_6_decimals, 1_500_000_000).unwrap(), "1500");
+    assert_eq!(to_ui_amount(&litesvm, &mint_9_decimals, 1_500_000_000).unwrap(), "1.5");
+    assert_eq!(to_ui_amount(&litesvm, &mint_9_decimals, 1).unwrap(), "0.000000001");
+    assert!(from_ui_amount(&litesvm, &mint_6_decimals, "0.0000001").is_err());
+    assert!(from_ui_amount(&litesvm, &mint_6_decimals, "1.5 tokens").is_err());
+    assert!(from_ui_amount(&litesvm, &Pubkey::new_unique(), "1").is_err());
+}
+
+#[test]
+fn test_nft_helpers_need_token_metadata_program() {
+    let mut litesvm = LiteSVM::new();
+    let authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+    // LiteSVM doesn't ship Token Metadata, so creating an NFT fails before sending anything
+    let result = create_nft(&mut litesvm, &authority, "Kite #1", "KITE", "https://example.com/1.json", None);
+    match result {
+        Err(SolanaKiteError::ProgramDeploymentFailed(message)) => {
+            assert!(message.contains("deploy_token_metadata_program"), "unexpected message: {}", message);
+        }
+        other => panic!("Expected ProgramDeploymentFailed, got {:?}", other),
+    }
+    assert!(create_collection_nft(&mut litesvm, &authority, "Kites", "KITE", "https://example.com/kites.json").is_err());
+    assert_eq!(litesvm.get_balance(&authority.pubkey()).unwrap(), 1_000_000_000);
+
+    let result = deploy_token_metadata_program(&mut litesvm, "tests/fixtures/missing_mpl_token_metadata.so");
+    assert!(matches!(result, Err(SolanaKiteError::ProgramDeploymentFailed(_))));
+}
+
+#[test]
+#[ignore = "requires tests/fixtures/mpl_token_metadata.so, see tests/fixtures/README.md"]
+fn test_create_nft_in_collection() {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/mpl_token_metadata.so");
+
+    let mut litesvm = LiteSVM::new();
+    deploy_token_metadata_program(&mut litesvm, fixture).unwrap();
+    let authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+
+    let collection = create_collection_nft(&mut litesvm, &authority, "Kites", "KITE", "https://example.com/kites.json").unwrap();
+    let nft = create_nft(
+        &mut litesvm,
+        &authority,
+        "Kite #1",
+        "KITE",
+        "https://example.com/1.json",
+        Some(&collection.mint),
+    ).unwrap();
+
+    // Token Metadata pads strings with null bytes
+    let metadata = Metadata::from_bytes(&litesvm.get_account(&nft.metadata).unwrap().data).unwrap();
+    assert_eq!(metadata.mint, nft.mint);
+    assert_eq!(metadata.update_authority, authority.pubkey());
+    assert_eq!(metadata.name.trim_end_matches('\0'), "Kite #1");
+    assert_eq!(metadata.symbol.trim_end_matches('\0'), "KITE");
+    assert_eq!(metadata.uri.trim_end_matches('\0'), "https://example.com/1.json");
+    let creators = metadata.creators.unwrap();
+    assert_eq!(creators.len(), 1);
+    assert_eq!(creators[0].address, authority.pubkey());
+    assert!(creators[0].verified);
+    assert_eq!(creators[0].share, 100);
+    let nft_collection = metadata.collection.unwrap();
+    assert_eq!(nft_collection.key, collection.mint);
+    assert!(nft_collection.verified);
+
+    let master_edition = MasterEdition::from_bytes(&litesvm.get_account(&nft.master_edition).unwrap().data).unwrap();
+    assert_eq!(master_edition.max_supply, Some(0));
+
+    // The master edition takes over the mint, so no more tokens can be minted
+    let mint = get_mint(&litesvm, &nft.mint).unwrap();
+    assert_eq!(mint.supply, 1);
+    assert_eq!(mint.decimals, 0);
+    assert_eq!(mint.mint_authority, Some(nft.master_edition));
+    assert_token_balance(&litesvm, &nft.token_account, 1, "NFT balance");
+
+    // Verifying the NFT counts it in the sized collection
+    let collection_metadata = Metadata::from_bytes(&litesvm.get_account(&collection.metadata).unwrap().data).unwrap();
+    assert!(collection_metadata.collection.is_none());
+    assert!(matches!(collection_metadata.collection_details, Some(CollectionDetails::V1 { size: 1 })));
+    assert_eq!(get_mint(&litesvm, &collection.mint).unwrap().supply, 1);
+}
+
+#[test]
+fn test_idempotent_and_non_associated_token_accounts() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let mut litesvm = LiteSVM::new();
+        let payer = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let owner = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+        let mint = create_token_mint(&mut litesvm, &payer, 6, None, None, &token_program).unwrap();
+
+        // Creating an existing associated token account fails, unless it's idempotent
+        let ata = create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &payer, &token_program).unwrap();
+        assert!(create_associated_token_account(&mut litesvm, &owner.pubkey(), &mint, &payer, &token_program).is_err());
+        let same_ata =
+            create_associated_token_account_idempotent(&mut litesvm, &owner.pubkey(), &mint, &payer, &token_program).unwrap();
+        assert_eq!(same_ata, ata);
+        let same_ata =
+            create_associated_token_account_idempotent(&mut litesvm, &owner.pubkey(), &mint, &payer, &token_program).unwrap();
+        assert_eq!(same_ata, ata);
+        let other_owner = Pubkey::new_unique();
+        let new_ata =
+            create_associated_token_account_idempotent(&mut litesvm, &other_owner, &mint, &payer, &token_program).unwrap();
+        assert_eq!(get_token_account(&litesvm, &new_ata).unwrap().owner, other_owner);
+
+        // A vault at a PDA, owned by a PDA
+        let program_id = Pubkey::new_unique();
+        let authority_seeds = seeds!["authority"];
+        let vault_seeds = seeds!["vault", mint];
+        let (vault_authority, _) = get_pda_and_bump(&authority_seeds, &program_id);
+        let (vault_address, _) = get_pda_and_bump(&vault_seeds, &program_id);
+        let vault =
+            create_token_account(&mut litesvm, &vault_authority, &mint, &payer, Some(vault_address), &token_program).unwrap();
+        assert_eq!(vault, vault_address);
+        let vault_state = get_token_account(&litesvm, &vault).unwrap();
+        assert_eq!(vault_state.owner, vault_authority);
+        assert_eq!(vault_state.mint, mint);
+        assert_eq!(vault_state.token_program, token_program);
+
+        mint_tokens_to_account(&mut litesvm, &mint, &vault, 1_000_000, &payer, &token_program).unwrap();
+        assert_token_balance(&litesvm, &vault, 1_000_000, "Vault should receive minted tokens");
+
+        // An existing account at the address is left alone
+        let result = create_token_account(&mut litesvm, &owner.pubkey(), &mint, &payer, Some(vault_address), &token_program);
+        assert!(matches!(result, Err(SolanaKiteError::TokenOperationFailed(_))));
+        let vault_state = get_token_account(&litesvm, &vault).unwrap();
+        assert_eq!(vault_state.owner, vault_authority);
+        assert_token_balance(&litesvm, &vault, 1_000_000, "Vault balance should be unchanged");
+
+        // A second account for the same owner and mint, at a generated address, with its rent paid by the payer
+        let balance_before = litesvm.get_balance(&payer.pubkey()).unwrap();
+        let second_account = create_token_account(&mut litesvm, &owner.pubkey(), &mint, &payer, None, &token_program).unwrap();
+        assert_ne!(second_account, ata);
+        let second_account_lamports = litesvm.get_balance(&second_account).unwrap();
+        assert!(litesvm.get_balance(&payer.pubkey()).unwrap() < balance_before - second_account_lamports);
+        transfer_tokens_checked(&mut litesvm, &mint, &ata, &second_account, 0, &owner, &token_program).unwrap();
+
+        // The mint must belong to the token program
+        let other_program = if token_program == spl_token::ID { spl_token_2022::ID } else { spl_token::ID };
+        assert!(create_token_account(&mut litesvm, &owner.pubkey(), &mint, &payer, None, &other_program).is_err());
+    }
+
+    // Token-2022 accounts get room for the extensions the mint requires
+    let mut litesvm = LiteSVM::new();
+    let payer = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let mint = MintBuilder::new(&payer, 6).transfer_fee(100, 1_000).create(&mut litesvm).unwrap();
+    let account = create_token_account(&mut litesvm, &payer.pubkey(), &mint, &payer, None, &spl_token_2022::ID).unwrap();
+    let account_state = get_token_account(&litesvm, &account).unwrap();
+    assert!(account_state.extension_types.contains(&spl_token_2022::extension::ExtensionType::TransferFeeAmount));
+}
+
+#[test]
+fn test_upgradeable_program_deployment() {
+    let mut litesvm = LiteSVM::new();
+    let upgrade_authority = create_wallet(&mut litesvm, 10_000_000_000).unwrap();
+    let new_authority = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let program_id = Pubkey::new_unique();
+
+    // Use binaries LiteSVM ships with: the token program, then the (smaller) memo program
+    let write_binary = |program: &Pubkey| {
+        let path = std::env::temp_dir().join(format!("{}.so", Pubkey::new_unique()));
+        std::fs::write(&path, litesvm.get_account(program).unwrap().data).unwrap();
+        path
+    };
+    let token_binary = write_binary(&spl_token::ID);
+    let memo_binary = write_binary(&"MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse().unwrap());
+
+    deploy_upgradeable_program(&mut litesvm, &program_id, &token_binary, Some(&upgrade_authority.pubkey())).unwrap();
+    let program_account = litesvm.get_account(&program_id).unwrap();
+    assert!(program_account.executable);
+    assert_eq!(program_account.owner, solana_sdk_ids::bpf_loader_upgradeable::ID);
+    assert_eq!(get_upgrade_authority(&litesvm, &program_id).unwrap(), Some(upgrade_authority.pubkey()));
+
+    // The token program rejects memo instruction data
+    let send_memo = |litesvm: &mut LiteSVM, text: &str| {
+        let instruction = solana_instruction::Instruction::new_with_bytes(program_id, text.as_bytes(), vec![]);
+        send_transaction_from_instructions(litesvm, vec![instruction], &[&upgrade_authority], &upgrade_authority.pubkey())
+    };
+    assert!(send_memo(&mut litesvm, "v1").is_err());
+
+    // Only the upgrade authority can upgrade
+    assert!(upgrade_program(&mut litesvm, &program_id, &memo_binary, &new_authority).is_err());
+    upgrade_program(&mut litesvm, &program_id, &memo_binary, &upgrade_authority).unwrap();
+    let result = send_memo(&mut litesvm, "v2").unwrap();
+    assert!(result.logs.iter().any(|log| log.contains("Memo (len 2)")), "logs: {:?}", result.logs);
+
+    // Upgrading again, to a bigger binary, in the same slot
+    upgrade_program(&mut litesvm, &program_id, &token_binary, &upgrade_authority).unwrap();
+    assert!(send_memo(&mut litesvm, "v3").is_err());
+
+    // Hand over the upgrade authority, then make the program immutable
+    set_upgrade_authority(&mut litesvm, &program_id, Some(&new_authority.pubkey()), &upgrade_authority).unwrap();
+    assert_eq!(get_upgrade_authority(&litesvm, &program_id).unwrap(), Some(new_authority.pubkey()));
+    assert!(upgrade_program(&mut litesvm, &program_id, &memo_binary, &upgrade_authority).is_err());
+    set_upgrade_authority(&mut litesvm, &program_id, None, &new_authority).unwrap();
+    assert_eq!(get_upgrade_authority(&litesvm, &program_id).unwrap(), None);
+    assert!(upgrade_program(&mut litesvm, &program_id, &memo_binary, &new_authority).is_err());
+
+    // Programs deployed with deploy_program aren't upgradeable
+    assert!(get_upgrade_authority(&litesvm, &spl_token::ID).is_err());
+
+    // Upgrading past the binary's original size extends the ProgramData account, keeping it rent exempt
+    let growing_program_id = Pubkey::new_unique();
+    deploy_upgradeable_program(&mut litesvm, &growing_program_id, &memo_binary, Some(&upgrade_authority.pubkey())).unwrap();
+    let programdata_address = solana_loader_v3_interface::get_program_data_address(&growing_program_id);
+    upgrade_program(&mut litesvm, &growing_program_id, &token_binary, &upgrade_authority).unwrap();
+    let token_binary_len = std::fs::metadata(&token_binary).unwrap().len() as usize;
+    let programdata_account = litesvm.get_account(&programdata_address).unwrap();
+    assert_eq!(
+        programdata_account.data.len(),
+        solana_loader_v3_interface::state::UpgradeableLoaderState::size_of_programdata(token_binary_len)
+    );
+    assert_eq!(programdata_account.lamports, litesvm.minimum_balance_for_rent_exemption(programdata_account.data.len()));
+
+    std::fs::remove_file(token_binary).unwrap();
+    std::fs::remove_file(memo_binary).unwrap();
+}
+
+#[test]
+fn test_deploy_program_from_bytes_and_paths() {
+    let mut litesvm = LiteSVM::new();
+    let payer = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let memo_program: Pubkey = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse().unwrap();
+    let memo_binary = litesvm.get_account(&memo_program).unwrap().data;
+
+    let send_memo = |litesvm: &mut LiteSVM, program_id: &Pubkey, text: &str| {
+        let instruction = solana_instruction::Instruction::new_with_bytes(*program_id, text.as_bytes(), vec![]);
+        send_transaction_from_instructions(litesvm, vec![instruction], &[&payer], &payer.pubkey())
+    };
+
+    let from_bytes = Pubkey::new_unique();
+    deploy_program_from_bytes(&mut litesvm, &from_bytes, &memo_binary).unwrap();
+    let result = send_memo(&mut litesvm, &from_bytes, "from bytes").unwrap();
+    assert!(result.logs.iter().any(|log| log.contains("Memo (len 10)")), "logs: {:?}", result.logs);
+
+    // Paths can be a &str, Path or PathBuf
+    let path = std::env::temp_dir().join(format!("{}.so", Pubkey::new_unique()));
+    std::fs::write(&path, &memo_binary).unwrap();
+    let from_path_buf = Pubkey::new_unique();
+    deploy_program(&mut litesvm, &from_path_buf, &path).unwrap();
+    send_memo(&mut litesvm, &from_path_buf, "from PathBuf").unwrap();
+    let from_str = Pubkey::new_unique();
+    deploy_program(&mut litesvm, &from_str, path.to_str().unwrap()).unwrap();
+    send_memo(&mut litesvm, &from_str, "from str").unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(
+        deploy_program(&mut litesvm, &Pubkey::new_unique(), &path),
+        Err(SolanaKiteError::ProgramDeploymentFailed(_))
+    ));
+    assert!(matches!(
+        deploy_program_from_bytes(&mut litesvm, &Pubkey::new_unique(), b"not a program"),
+        Err(SolanaKiteError::ProgramDeploymentFailed(_))
+    ));
+}
+
+#[test]
+fn test_deploy_anchor_workspace() {
+    let mut litesvm = LiteSVM::new();
+    let payer = create_wallet(&mut litesvm, 1_000_000_000).unwrap();
+    let memo_program: Pubkey = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse().unwrap();
+    let memo_binary = litesvm.get_account(&memo_program).unwrap().data;
+
+    // A workspace laid out the way `anchor build` leaves it, with the memo program standing in for both programs.
+    // token-vault is only listed for devnet, so it's skipped even though its binary is present
+    let workspace_root = std::env::temp_dir().join(format!("anchor-workspace-{}", Pubkey::new_unique()));
+    let deploy_directory = workspace_root.join("target").join("deploy");
+    std::fs::create_dir_all(&deploy_directory).unwrap();
+    std::fs::write(
+        workspace_root.join("Anchor.toml"),
+        "[programs.localnet]\nescrow = \"11111111111111111111111111111111\"\n\n\
+         [programs.devnet]\ntoken-vault = \"11111111111111111111111111111111\"\n\n\
+         [provider]\ncluster = \"localnet\"\n",
+    )
+    .unwrap();
+    let mut expected_program_ids = std::collections::HashMap::new();
+    for (program_name, artifact_name) in [("escrow", "escrow"), ("token-vault", "token_vault")] {
+        let keypair = solana_keypair::Keypair::new();
+        solana_keypair::write_keypair_file(&keypair, deploy_directory.join(format!("{}-keypair.json", artifact_name))).unwrap();
+        std::fs::write(deploy_directory.join(format!("{}.so", artifact_name)), &memo_binary).unwrap();
+        if program_name == "escrow" {
+            expected_program_ids.insert(program_name.to_string(), keypair.pubkey());
+        }
+    }
+
+    let program_ids = deploy_anchor_workspace(&mut litesvm, &workspace_root).unwrap();
+    assert_eq!(program_ids, expected_program_ids);
+
+    // Program IDs come from the keypairs, not the IDs written in Anchor.toml
+    for program_id in program_ids.values() {
+        let instruction = solana_instruction::Instruction::new_with_bytes(*program_id, b"deployed", vec![]);
+        send_transaction_from_instructions(&mut litesvm, vec![instruction], &[&payer], &payer.pubkey()).unwrap();
+    }
+
+    // A missing program binary is reported
+    std::fs::remove_file(deploy_directory.join("escrow.so")).unwrap();
+    assert!(matches!(
+        deploy_anchor_workspace(&mut litesvm, &workspace_root),
+        Err(SolanaKiteError::ProgramDeploymentFailed(_))
+    ));
+
+    std::fs::remove_dir_all(&workspace_root).unwrap();
+    assert!(deploy_anchor_workspace(&mut litesvm, &workspace_root).is_err());
+}